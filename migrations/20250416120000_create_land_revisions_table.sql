@@ -0,0 +1,10 @@
+-- Create land revisions table.
+CREATE TABLE IF NOT EXISTS land_revisions
+(
+    id INTEGER PRIMARY KEY NOT NULL,
+    mayhem_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    land BLOB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS land_revisions_mayhem_id ON land_revisions (mayhem_id, created_at);
@@ -0,0 +1,8 @@
+-- Create saves table used by the sqlite storage backend.
+CREATE TABLE IF NOT EXISTS saves
+(
+    mayhem_id TEXT PRIMARY KEY NOT NULL,
+    land BLOB,
+    currency BLOB,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,15 @@
+-- Create currency transactions table.
+CREATE TABLE IF NOT EXISTS currency_transactions
+(
+    id INTEGER PRIMARY KEY NOT NULL,
+    mayhem_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    reason TEXT,
+    amount INTEGER NOT NULL,
+    balance_before INTEGER NOT NULL,
+    balance_after INTEGER NOT NULL,
+    request_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS currency_transactions_mayhem_id ON currency_transactions (mayhem_id, created_at);
@@ -0,0 +1,14 @@
+-- Create purchases table keeping every emulated store order.
+CREATE TABLE IF NOT EXISTS purchases
+(
+    id INTEGER PRIMARY KEY NOT NULL,
+    mayhem_id TEXT NOT NULL,
+    order_id TEXT UNIQUE NOT NULL,
+    product_id TEXT NOT NULL,
+    platform INTEGER,
+    donuts INTEGER NOT NULL,
+    state INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS purchases_mayhem_id ON purchases (mayhem_id, created_at);
@@ -0,0 +1,3 @@
+-- Track land levels for matchmaking and let players opt out of it.
+ALTER TABLE users ADD COLUMN land_level INTEGER;
+ALTER TABLE users ADD COLUMN matchmaking_opt_out BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Create friendships table. every friendship is stored in both directions once accepted.
+CREATE TABLE IF NOT EXISTS friendships
+(
+    mayhem_id TEXT NOT NULL,
+    friend_id TEXT NOT NULL,
+    accepted BOOLEAN NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (mayhem_id, friend_id)
+);
+
+CREATE INDEX IF NOT EXISTS friendships_friend_id ON friendships (friend_id, accepted);
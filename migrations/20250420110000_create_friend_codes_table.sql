@@ -0,0 +1,7 @@
+-- Create friend codes table. each user has a single code other players can redeem.
+CREATE TABLE IF NOT EXISTS friend_codes
+(
+    mayhem_id TEXT PRIMARY KEY NOT NULL,
+    code TEXT UNIQUE NOT NULL,
+    created_at INTEGER NOT NULL
+);
@@ -0,0 +1,16 @@
+-- Create land_events table. events sent by visitors are resent until the owner saves the land, delivered_at marks the acknowledgement.
+CREATE TABLE IF NOT EXISTS land_events
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_id TEXT NOT NULL,
+    mayhem_id TEXT NOT NULL,
+    from_mayhem_id TEXT NOT NULL,
+    event_type TEXT,
+    target_entity_id TEXT,
+    event BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    sent_at INTEGER,
+    delivered_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS land_events_mayhem_id ON land_events (mayhem_id, delivered_at);
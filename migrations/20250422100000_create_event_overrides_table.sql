@@ -0,0 +1,10 @@
+-- Create event_overrides table. players listed here keep their own event and lobby clock.
+CREATE TABLE IF NOT EXISTS event_overrides
+(
+    mayhem_id TEXT PRIMARY KEY NOT NULL,
+    event INTEGER NOT NULL,
+    selected_at INTEGER NOT NULL,
+    frozen_at INTEGER,
+    paused INTEGER NOT NULL DEFAULT 0,
+    clock_offset INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,192 @@
+use crate::{
+    config::OPTIONS,
+    database::Database,
+    protos::{LandError, RawLand, RawLandBackupResponse},
+    util::millis_from_unix_epoch,
+};
+use tracing::{debug, instrument, warn};
+
+/// amount of revisions sent to the client at once, each holds a whole land
+const BACKUPS_PER_PAGE: i64 = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupControllerError {
+    #[error("No land revision exists for that date")]
+    NotFound,
+    #[error(transparent)]
+    ProtoLandError(#[from] LandError),
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupController {
+    db: Database,
+}
+
+impl Default for BackupController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+impl BackupController {
+    /// stores `land` as a new revision of `mayhem_id` and prunes revisions exceeding the configured retention
+    #[instrument(skip(self, land))]
+    pub async fn record(
+        &self,
+        mayhem_id: &String,
+        land: &[u8],
+    ) -> Result<u64, BackupControllerError> {
+        const QUERY: &str = r#"
+            INSERT INTO land_revisions (mayhem_id, created_at, land)
+            VALUES (?, ?, ?)"#;
+
+        let date = millis_from_unix_epoch()? as u64;
+
+        sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(date as i64)
+            .bind(land)
+            .execute(&self.db)
+            .await?;
+
+        debug!("recorded land revision {date} for {mayhem_id}");
+
+        self.prune(mayhem_id).await?;
+
+        Ok(date)
+    }
+
+    /// removes revisions of `mayhem_id` which are either too old or exceed the amount of kept revisions
+    #[instrument(skip(self))]
+    pub async fn prune(&self, mayhem_id: &String) -> Result<u64, BackupControllerError> {
+        const QUERY_COUNT: &str = r#"
+            DELETE FROM land_revisions
+            WHERE mayhem_id = ? AND id NOT IN (
+                SELECT id FROM land_revisions
+                WHERE mayhem_id = ?
+                ORDER BY created_at DESC, id DESC
+                LIMIT ?
+            )"#;
+        const QUERY_AGE: &str = r#"
+            DELETE FROM land_revisions
+            WHERE mayhem_id = ? AND created_at < ?"#;
+
+        let (max_count, max_age) = {
+            let options = OPTIONS.take();
+            (options.land_revisions, options.land_revisions_max_age)
+        };
+        let mut pruned = 0;
+
+        if max_count > 0 {
+            pruned += sqlx::query(QUERY_COUNT)
+                .bind(mayhem_id)
+                .bind(mayhem_id)
+                .bind(max_count as i64)
+                .execute(&self.db)
+                .await?
+                .rows_affected();
+        }
+
+        if max_age > 0 {
+            let oldest = millis_from_unix_epoch()? as i64 - (max_age as i64 * 1000);
+
+            pruned += sqlx::query(QUERY_AGE)
+                .bind(mayhem_id)
+                .bind(oldest)
+                .execute(&self.db)
+                .await?
+                .rows_affected();
+        }
+
+        if pruned > 0 {
+            debug!("pruned {pruned} land revisions of {mayhem_id}");
+        }
+
+        Ok(pruned)
+    }
+
+    /// lists the dates of all stored revisions of `mayhem_id`, newest first
+    #[instrument(skip(self))]
+    pub async fn list(&self, mayhem_id: &String) -> Result<Vec<u64>, BackupControllerError> {
+        const QUERY: &str = r#"
+            SELECT created_at FROM land_revisions
+            WHERE mayhem_id = ?
+            ORDER BY created_at DESC, id DESC"#;
+
+        Ok(sqlx::query_scalar::<_, i64>(QUERY)
+            .bind(mayhem_id)
+            .fetch_all(&self.db)
+            .await?
+            .into_iter()
+            .map(|date| date as u64)
+            .collect())
+    }
+
+    /// loads the revision of `mayhem_id` stored at `date`
+    #[instrument(skip(self))]
+    pub async fn get(
+        &self,
+        mayhem_id: &String,
+        date: u64,
+    ) -> Result<RawLand, BackupControllerError> {
+        const QUERY: &str = r#"
+            SELECT land FROM land_revisions
+            WHERE mayhem_id = ? AND created_at = ?
+            ORDER BY id DESC
+            LIMIT 1"#;
+
+        let land = sqlx::query_scalar::<_, Vec<u8>>(QUERY)
+            .bind(mayhem_id)
+            .bind(date as i64)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or(BackupControllerError::NotFound)?;
+
+        Ok(RawLand::decode(land)?)
+    }
+
+    /// returns the stored revisions of `mayhem_id` on `page`, newest first.
+    ///
+    /// the lands are served as stored, see `RawLand`
+    #[instrument(skip(self))]
+    pub async fn backups(
+        &self,
+        mayhem_id: &String,
+        page: u32,
+    ) -> Result<RawLandBackupResponse, BackupControllerError> {
+        const QUERY: &str = r#"
+            SELECT created_at, land FROM land_revisions
+            WHERE mayhem_id = ?
+            ORDER BY created_at DESC, id DESC
+            LIMIT ? OFFSET ?"#;
+
+        let revisions = sqlx::query_as::<_, (i64, Vec<u8>)>(QUERY)
+            .bind(mayhem_id)
+            .bind(BACKUPS_PER_PAGE)
+            .bind(page as i64 * BACKUPS_PER_PAGE)
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(RawLandBackupResponse::new(
+            revisions
+                .into_iter()
+                .filter_map(|(date, land)| match RawLand::decode(land) {
+                    Ok(land) => Some((date as u64, land)),
+                    Err(e) => {
+                        warn!("skipping corrupt land revision {date} of {mayhem_id}: {e}");
+
+                        None
+                    }
+                }),
+        ))
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    app::models::{client_config::ClientConfigToml, dashboard::ConfigEntry},
+    config::OPTIONS,
+    protos::data::{client_config_response::ClientConfigItem, ClientConfigResponse},
+    util::CachedToml,
+};
+use tracing::instrument;
+
+/// a missing file means no overrides
+static OVERRIDES: CachedToml<ClientConfigToml> =
+    CachedToml::new("client_config.toml", ClientConfigToml::default);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientConfigControllerError {
+    #[error("Client config items need a name")]
+    InvalidName,
+    #[error("failed to decode json")]
+    JSONDecodeError(#[from] serde_json::Error),
+    #[error("failed to encode client config")]
+    Serialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfigController {}
+
+impl ClientConfigController {
+    /// returns the embedded `ClientConfig.json` with the client version policy and the items of `client_config.toml` applied
+    #[instrument(skip(self))]
+    pub fn client_config(&self) -> Result<ClientConfigResponse, ClientConfigControllerError> {
+        let mut client_config = Self::embedded()?;
+        let overrides = OVERRIDES.load()?;
+        let policy = OPTIONS.take().client_versions.clone();
+
+        // the game prompts for upgrades itself based on these items
+        for item in client_config.items.iter_mut() {
+            let Some(name) = item.name.as_deref() else {
+                continue;
+            };
+            let version = if let Some(platform) = name.strip_prefix("MinimumVersion.") {
+                policy.rules(Some(platform)).minimum.clone()
+            } else if let Some(platform) = name.strip_prefix("CurrentVersion.") {
+                policy.rules(Some(platform)).current.clone()
+            } else {
+                None
+            };
+
+            if let Some(version) = version {
+                item.value = Some(version);
+            }
+        }
+
+        for (name, value) in overrides.items {
+            match client_config
+                .items
+                .iter_mut()
+                .find(|item| item.name.as_ref() == Some(&name))
+            {
+                Some(item) => item.value = Some(value),
+                None => {
+                    let id = client_config
+                        .items
+                        .iter()
+                        .filter_map(|item| item.client_config_id)
+                        .max()
+                        .map_or(0, |id| id + 1);
+
+                    client_config.items.push(ClientConfigItem {
+                        client_config_id: Some(id),
+                        name: Some(name),
+                        value: Some(value),
+                    });
+                }
+            }
+        }
+
+        Ok(client_config)
+    }
+
+    /// lists every item with its embedded and overridden value
+    #[instrument(skip(self))]
+    pub fn entries(&self) -> Result<Vec<ConfigEntry>, ClientConfigControllerError> {
+        let mut overrides = OVERRIDES.load()?.items;
+
+        let mut entries = Self::embedded()?
+            .items
+            .into_iter()
+            .filter_map(|item| item.name.map(|name| (name, item.value)))
+            .map(|(name, default)| ConfigEntry {
+                value: overrides.remove(&name),
+                name,
+                default,
+            })
+            .collect::<Vec<_>>();
+
+        entries.extend(overrides.into_iter().map(|(name, value)| ConfigEntry {
+            name,
+            default: None,
+            value: Some(value),
+        }));
+
+        Ok(entries)
+    }
+
+    /// overrides the item `name` in `client_config.toml`, `None` restores the embedded value
+    #[instrument(skip(self))]
+    pub fn set_item(
+        &self,
+        name: String,
+        value: Option<String>,
+    ) -> Result<(), ClientConfigControllerError> {
+        let name = name.trim().to_owned();
+        if name.is_empty() {
+            return Err(ClientConfigControllerError::InvalidName);
+        }
+
+        let mut overrides = OVERRIDES.load()?;
+        match value {
+            Some(value) => overrides.items.insert(name, value),
+            None => overrides.items.remove(&name),
+        };
+
+        OVERRIDES.save(&overrides)
+    }
+
+    fn embedded() -> Result<ClientConfigResponse, ClientConfigControllerError> {
+        Ok(serde_json::from_str(crate::assets::CLIENT_CONFIG)?)
+    }
+}
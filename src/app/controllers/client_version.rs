@@ -0,0 +1,288 @@
+use crate::{
+    app::models::dashboard::{ClientVersionPolicy, SeenClientVersion, VersionRules, VersionStatus},
+    config::OPTIONS,
+    util::{config_path, secs_from_unix_epoch},
+};
+use axum::http::HeaderMap;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BTreeMap,
+    sync::Mutex,
+};
+use tracing::{debug, info, instrument};
+
+/// amount of versions remembered for the dashboard, the oldest ones are dropped first
+const MAX_SEEN: usize = 256;
+
+lazy_static::lazy_static! {
+    /// last time a version connected by platform and version
+    static ref SEEN: Mutex<BTreeMap<(Option<String>, String), u64>> = Mutex::new(BTreeMap::new());
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientVersionControllerError {
+    #[error("{0} is not a valid version")]
+    InvalidVersion(String),
+    #[error("failed to save server config")]
+    Config(#[from] anyhow::Error),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientVersionController {}
+
+impl ClientVersionController {
+    /// checks `version` of a client on `platform` against the policy and remembers it for the dashboard.
+    ///
+    /// versions which can not be parsed are allowed, but not remembered
+    #[instrument(skip(self))]
+    pub fn check(&self, platform: Option<&str>, version: &str) -> VersionStatus {
+        let status = Self::status(OPTIONS.take().client_versions.rules(platform), version);
+
+        if Self::parse(version).is_some() {
+            if let Ok(now) = secs_from_unix_epoch() {
+                Self::remember(platform, version, now);
+            }
+        }
+
+        if status == VersionStatus::Rejected {
+            info!("rejected client {version} of {platform:?}");
+        }
+
+        status
+    }
+
+    /// client versions which connected since the server started, newest first
+    #[instrument(skip(self))]
+    pub fn seen(&self) -> Vec<SeenClientVersion> {
+        let policy = OPTIONS.take().client_versions.clone();
+        let mut seen = SEEN
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((platform, version), last_seen)| SeenClientVersion {
+                status: Self::status(policy.rules(platform.as_deref()), version),
+                platform: platform.clone(),
+                version: version.clone(),
+                last_seen: *last_seen,
+            })
+            .collect::<Vec<_>>();
+        seen.sort_by_key(|seen| Reverse(seen.last_seen));
+
+        seen
+    }
+
+    /// the headers are sent before authentication, so the map is capped at `MAX_SEEN` entries
+    fn remember(platform: Option<&str>, version: &str, now: u64) {
+        let mut seen = SEEN.lock().unwrap();
+        seen.insert(
+            (platform.map(str::to_ascii_lowercase), version.to_owned()),
+            now,
+        );
+
+        while seen.len() > MAX_SEEN {
+            let Some(oldest) = seen
+                .iter()
+                .min_by_key(|(_, last_seen)| **last_seen)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            seen.remove(&oldest);
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub fn policy(&self) -> ClientVersionPolicy {
+        OPTIONS.take().client_versions.clone()
+    }
+
+    /// validates `policy` and persists it in `server.toml`
+    #[instrument(skip(self))]
+    pub fn set_policy(
+        &self,
+        policy: ClientVersionPolicy,
+    ) -> Result<(), ClientVersionControllerError> {
+        for rules in std::iter::once(&policy.default).chain(policy.platforms.values()) {
+            for version in rules
+                .minimum
+                .iter()
+                .chain(rules.current.iter())
+                .chain(rules.allowed.iter())
+            {
+                if Self::parse(version).is_none() {
+                    return Err(ClientVersionControllerError::InvalidVersion(
+                        version.clone(),
+                    ));
+                }
+            }
+        }
+
+        let path = config_path("server.toml").map_err(anyhow::Error::from)?;
+
+        let mut server_options = OPTIONS.take();
+        server_options.client_versions = policy;
+        server_options.save(path)?;
+
+        Ok(())
+    }
+
+    /// version the client reports in the `client_version` header
+    pub fn version(headers: &HeaderMap) -> Option<String> {
+        Self::header(headers, "client_version")
+    }
+
+    /// platform the client reports in the `platform` header
+    pub fn platform(headers: &HeaderMap) -> Option<String> {
+        Self::header(headers, "platform")
+    }
+
+    fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn status(rules: &VersionRules, version: &str) -> VersionStatus {
+        let Some(parsed) = Self::parse(version) else {
+            debug!("client reported the invalid version {version}");
+
+            return VersionStatus::Allowed;
+        };
+        let compare = |other: &str| Self::parse(other).map(|other| Self::compare(&parsed, &other));
+        let older_than =
+            |other: &Option<String>| other.as_deref().and_then(compare) == Some(Ordering::Less);
+
+        // `4.69` and `4.69.0` are the same version
+        let status = if rules
+            .allowed
+            .iter()
+            .any(|allowed| compare(allowed) == Some(Ordering::Equal))
+        {
+            VersionStatus::Allowed
+        } else if older_than(&rules.minimum) {
+            VersionStatus::Rejected
+        } else if older_than(&rules.current) {
+            VersionStatus::UpgradeAvailable
+        } else {
+            VersionStatus::Allowed
+        };
+        debug!("client version {version} is {status:?}");
+
+        status
+    }
+
+    /// parses dotted versions like `4.69.0`
+    fn parse(version: &str) -> Option<Vec<u64>> {
+        version.split('.').map(|part| part.parse().ok()).collect()
+    }
+
+    /// compares versions, missing parts count as `0`
+    fn compare(a: &[u64], b: &[u64]) -> Ordering {
+        (0..a.len().max(b.len()))
+            .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClientVersionController;
+    use crate::app::models::dashboard::{VersionRules, VersionStatus};
+    use std::cmp::Ordering;
+
+    fn compare(a: &str, b: &str) -> Ordering {
+        ClientVersionController::compare(
+            &ClientVersionController::parse(a).unwrap(),
+            &ClientVersionController::parse(b).unwrap(),
+        )
+    }
+
+    fn rules(minimum: &str, current: &str, allowed: &[&str]) -> VersionRules {
+        VersionRules {
+            minimum: Some(minimum.to_owned()),
+            current: Some(current.to_owned()),
+            allowed: allowed.iter().map(|version| version.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_versions() {
+        assert_eq!(
+            ClientVersionController::parse("4.69.0"),
+            Some(vec![4, 69, 0])
+        );
+        assert_eq!(ClientVersionController::parse("4.69-beta"), None);
+        assert_eq!(ClientVersionController::parse("4..69"), None);
+        assert_eq!(ClientVersionController::parse(""), None);
+    }
+
+    #[test]
+    fn compare_treats_missing_parts_as_zero() {
+        assert_eq!(compare("4.69", "4.69.0"), Ordering::Equal);
+        assert_eq!(compare("4.69.0.0", "4.69"), Ordering::Equal);
+        assert_eq!(compare("4.69", "4.69.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_uses_numbers_instead_of_text() {
+        assert_eq!(compare("4.9", "4.10"), Ordering::Less);
+        assert_eq!(compare("10.0", "9.99"), Ordering::Greater);
+    }
+
+    #[test]
+    fn status_follows_minimum_and_current() {
+        let rules = rules("4.60", "4.69", &[]);
+
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.59.9"),
+            VersionStatus::Rejected
+        );
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.60"),
+            VersionStatus::UpgradeAvailable
+        );
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.69.0"),
+            VersionStatus::Allowed
+        );
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.70"),
+            VersionStatus::Allowed
+        );
+    }
+
+    #[test]
+    fn status_lets_allowed_versions_through() {
+        let rules = rules("4.60", "4.69", &["4.50"]);
+
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.50.0"),
+            VersionStatus::Allowed
+        );
+        assert_eq!(
+            ClientVersionController::status(&rules, "4.51"),
+            VersionStatus::Rejected
+        );
+    }
+
+    #[test]
+    fn status_allows_invalid_versions() {
+        let rules = rules("4.60", "4.69", &[]);
+
+        assert_eq!(
+            ClientVersionController::status(&rules, "unknown"),
+            VersionStatus::Allowed
+        );
+    }
+
+    #[test]
+    fn status_without_rules() {
+        assert_eq!(
+            ClientVersionController::status(&VersionRules::default(), "1.0"),
+            VersionStatus::Allowed
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    app::models::currency::{CurrencySource, CurrencyTransaction},
+    database::Database,
+    protos::data::CurrencyData,
+    util::millis_from_unix_epoch,
+};
+use tracing::{debug, instrument, warn};
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CurrencyControllerError {
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+#[derive(Debug, Clone)]
+pub struct CurrencyController {
+    db: Database,
+}
+
+impl Default for CurrencyController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+impl CurrencyController {
+    /// stores all `transactions` at once, either all of them get recorded or none
+    #[instrument(skip(self))]
+    pub async fn record(
+        &self,
+        transactions: &[CurrencyTransaction],
+    ) -> Result<(), CurrencyControllerError> {
+        const QUERY: &str = r#"
+            INSERT INTO currency_transactions
+            (mayhem_id, source, reason, amount, balance_before, balance_after, request_id, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#;
+
+        let mut tx = self.db.begin().await?;
+
+        for transaction in transactions {
+            sqlx::query(QUERY)
+                .bind(&transaction.mayhem_id)
+                .bind(transaction.source)
+                .bind(&transaction.reason)
+                .bind(transaction.amount)
+                .bind(transaction.balance_before)
+                .bind(transaction.balance_after)
+                .bind(&transaction.request_id)
+                .bind(transaction.created_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// lists the transactions of `mayhem_id`, newest first.
+    ///
+    /// `source` limits the result to transactions of that source, `limit` of `0` returns all transactions.
+    /// `offset` skips the newest transactions, which pages through the ledger
+    #[instrument(skip(self))]
+    pub async fn transactions(
+        &self,
+        mayhem_id: &str,
+        source: Option<CurrencySource>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<CurrencyTransaction>, CurrencyControllerError> {
+        const QUERY: &str = r#"
+            SELECT * FROM currency_transactions
+            WHERE mayhem_id = ? AND (? IS NULL OR source = ?)
+            ORDER BY created_at DESC, id DESC
+            LIMIT ? OFFSET ?"#;
+
+        // sqlite treats a negative limit as no limit
+        let limit = if limit == 0 { -1 } else { limit as i64 };
+
+        Ok(sqlx::query_as::<_, CurrencyTransaction>(QUERY)
+            .bind(mayhem_id)
+            .bind(source)
+            .bind(source)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.db)
+            .await?)
+    }
+
+    /// balance of `mayhem_id` after its latest transaction, `None` if none were recorded yet
+    #[instrument(skip(self))]
+    pub async fn balance(&self, mayhem_id: &str) -> Result<Option<i64>, CurrencyControllerError> {
+        const QUERY: &str = r#"
+            SELECT balance_after FROM currency_transactions
+            WHERE mayhem_id = ?
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1"#;
+
+        Ok(sqlx::query_scalar::<_, i64>(QUERY)
+            .bind(mayhem_id)
+            .fetch_optional(&self.db)
+            .await?)
+    }
+
+    /// compares the balance of `currency` with the ledger of `mayhem_id` and records the difference.
+    ///
+    /// saves which predate the ledger get their current balance recorded as initial transaction
+    #[instrument(skip(self))]
+    pub async fn reconcile(
+        &self,
+        mayhem_id: &str,
+        currency: &CurrencyData,
+    ) -> Result<(), CurrencyControllerError> {
+        let balance = currency.vc_balance.unwrap_or_default() as i64;
+
+        let transaction = match self.balance(mayhem_id).await? {
+            Some(ledger) if ledger == balance => return Ok(()),
+            Some(ledger) => {
+                warn!("currency of {mayhem_id} differs from ledger: {balance} != {ledger}");
+
+                CurrencyTransaction::new(
+                    mayhem_id,
+                    CurrencySource::Reconcile,
+                    None,
+                    balance - ledger,
+                    ledger,
+                    Uuid::new_v4().to_string(),
+                    millis_from_unix_epoch()? as i64,
+                )
+            }
+            None => {
+                debug!("starting ledger of {mayhem_id} with {balance}");
+
+                CurrencyTransaction::new(
+                    mayhem_id,
+                    CurrencySource::Initial,
+                    None,
+                    balance,
+                    0,
+                    Uuid::new_v4().to_string(),
+                    millis_from_unix_epoch()? as i64,
+                )
+            }
+        };
+
+        self.record(&[transaction]).await
+    }
+}
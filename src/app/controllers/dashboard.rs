@@ -1,19 +1,29 @@
 use super::{
     auth::{AuthController, AuthControllerError},
+    client_config::{ClientConfigController, ClientConfigControllerError},
+    client_version::{ClientVersionController, ClientVersionControllerError},
+    currency::{CurrencyController, CurrencyControllerError},
+    event_override::{EventOverrideController, EventOverrideControllerError},
     events::{EventController, EventControllerError},
+    friend::{FriendController, FriendControllerError},
+    gameplay_config::{GameplayConfigController, GameplayConfigControllerError},
     mayhem::{MayhemController, MayhemControllerError},
     user::{UserController, UserControllerError},
 };
 use crate::{
     app::models::{
         auth::{Role, UserId},
+        currency::{CurrencyTransaction, CURRENCY_TRANSACTIONS_PER_PAGE},
         dashboard::{
-            CreditsResponse, EventsResponse, ServerConfigResponse, Status, StatusResponse,
+            CatalogEvent, ClientVersionPolicy, ClientVersionsResponse, ConfigEntry,
+            ConfigReloadResponse, CreditsResponse, EventOverride, EventScheduleResponse,
+            EventsResponse, LobbyClockResponse, Player, PlaylistEntry, ServerConfigResponse,
+            Status, StatusResponse,
         },
-        events::TSTO_EVENTS,
+        friend::FriendsResponse,
     },
     config::OPTIONS,
-    util::{relative_path, DIRECTORIES, UPTIME},
+    util::{config_path, UPTIME},
 };
 use tracing::instrument;
 
@@ -22,8 +32,20 @@ pub enum DashboardControllerError {
     #[error(transparent)]
     AuthController(#[from] AuthControllerError),
     #[error(transparent)]
+    ClientConfigController(#[from] ClientConfigControllerError),
+    #[error(transparent)]
+    ClientVersionController(#[from] ClientVersionControllerError),
+    #[error(transparent)]
+    CurrencyController(#[from] CurrencyControllerError),
+    #[error(transparent)]
+    EventOverrideController(#[from] EventOverrideControllerError),
+    #[error(transparent)]
     EventController(#[from] EventControllerError),
     #[error(transparent)]
+    FriendController(#[from] FriendControllerError),
+    #[error(transparent)]
+    GameplayConfigController(#[from] GameplayConfigControllerError),
+    #[error(transparent)]
     UserController(#[from] UserControllerError),
     #[error(transparent)]
     MayhemController(#[from] MayhemControllerError),
@@ -34,7 +56,14 @@ pub enum DashboardControllerError {
 #[derive(Debug, Default, Clone)]
 pub struct DashboardController {
     auth: AuthController,
+    client_config: ClientConfigController,
+    client_versions: ClientVersionController,
+    currency: CurrencyController,
+    event_overrides: EventOverrideController,
     events: EventController,
+    friends: FriendController,
+    gameplay_config: GameplayConfigController,
+    mayhem: MayhemController,
     users: UserController,
 }
 
@@ -59,6 +88,137 @@ impl DashboardController {
         Ok(self.users.count().await?)
     }
 
+    #[instrument(skip(self))]
+    pub async fn get_player_list(&self) -> Result<Vec<Player>, DashboardControllerError> {
+        Ok(self.users.list().await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn set_matchmaking_opt_out(
+        &self,
+        mayhem_id: String,
+        opt_out: bool,
+    ) -> Result<bool, DashboardControllerError> {
+        Ok(self
+            .users
+            .set_matchmaking_opt_out(&mayhem_id, opt_out)
+            .await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_land_backups(
+        &self,
+        mayhem_id: String,
+    ) -> Result<Vec<u64>, DashboardControllerError> {
+        Ok(self.mayhem.list_land_backups(&mayhem_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn restore_land_backup(
+        &self,
+        mayhem_id: String,
+        date: u64,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.mayhem.restore_land_backup(&mayhem_id, date).await?)
+    }
+
+    /// lists the transactions of `mayhem_id` on `page`, newest first
+    #[instrument(skip(self))]
+    pub async fn get_currency_transactions(
+        &self,
+        mayhem_id: String,
+        page: u32,
+    ) -> Result<Vec<CurrencyTransaction>, DashboardControllerError> {
+        Ok(self
+            .currency
+            .transactions(
+                &mayhem_id,
+                None,
+                CURRENCY_TRANSACTIONS_PER_PAGE,
+                page.saturating_mul(CURRENCY_TRANSACTIONS_PER_PAGE),
+            )
+            .await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_event_override(
+        &self,
+        mayhem_id: String,
+    ) -> Result<Option<EventOverride>, DashboardControllerError> {
+        Ok(self.event_overrides.get(&mayhem_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn set_event_override(
+        &self,
+        mayhem_id: String,
+        event_override: Option<EventOverride>,
+    ) -> Result<(), DashboardControllerError> {
+        match event_override {
+            Some(event_override) => {
+                self.event_overrides
+                    .set(&mayhem_id, &event_override)
+                    .await?
+            }
+            None => {
+                self.event_overrides.remove(&mayhem_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_friends(
+        &self,
+        mayhem_id: String,
+    ) -> Result<FriendsResponse, DashboardControllerError> {
+        Ok(self.friends.list(&mayhem_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_friend_code(
+        &self,
+        mayhem_id: String,
+    ) -> Result<String, DashboardControllerError> {
+        Ok(self.friends.code(&mayhem_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn regenerate_friend_code(
+        &self,
+        mayhem_id: String,
+    ) -> Result<String, DashboardControllerError> {
+        Ok(self.friends.regenerate_code(&mayhem_id).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn redeem_friend_code(
+        &self,
+        mayhem_id: String,
+        code: String,
+    ) -> Result<String, DashboardControllerError> {
+        Ok(self.friends.redeem(&mayhem_id, &code).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn accept_friend_request(
+        &self,
+        mayhem_id: String,
+        requester: String,
+    ) -> Result<bool, DashboardControllerError> {
+        Ok(self.friends.accept(&mayhem_id, &requester).await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn remove_friend(
+        &self,
+        mayhem_id: String,
+        friend_id: String,
+    ) -> Result<bool, DashboardControllerError> {
+        Ok(self.friends.remove(&mayhem_id, &friend_id).await?)
+    }
+
     #[instrument(skip(self))]
     pub fn get_config(&self) -> Result<ServerConfigResponse, DashboardControllerError> {
         let options = OPTIONS.take().clone();
@@ -72,27 +232,131 @@ impl DashboardController {
         })
     }
 
-    #[instrument]
-    pub fn get_events() -> EventsResponse {
-        EventsResponse {
-            events: TSTO_EVENTS
-                .iter()
-                .map(|(k, v)| (*k, v.to_string()))
+    #[instrument(skip(self))]
+    pub fn get_events(&self) -> Result<EventsResponse, DashboardControllerError> {
+        Ok(EventsResponse {
+            events: self
+                .events
+                .catalog()?
+                .into_iter()
+                .map(|event| (event.start, event.name))
                 .collect(),
-            active: 0,
-        }
+            active: self.get_event()?.0,
+        })
     }
 
-    // TODO implement
     #[instrument(skip(self))]
     pub fn get_event(&self) -> Result<(u64, String), DashboardControllerError> {
         Ok(self.events.get_event()?)
     }
 
-    // TODO implement
+    #[instrument(skip(self))]
+    pub fn set_event(&self, ts: u64) -> Result<(), DashboardControllerError> {
+        Ok(self.events.set_event(ts)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn get_event_schedule(&self) -> Result<EventScheduleResponse, DashboardControllerError> {
+        const UPCOMING: usize = 10;
+
+        let rotation = OPTIONS.take().event_rotation.clone();
+
+        Ok(EventScheduleResponse {
+            enabled: rotation.enabled,
+            interval: rotation.interval,
+            playlist: self.events.playlist()?,
+            upcoming: self.events.schedule(UPCOMING)?,
+        })
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_event_rotation(
+        &self,
+        enabled: bool,
+        interval: u64,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.events.set_rotation(enabled, interval)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_event_playlist(
+        &self,
+        playlist: Vec<PlaylistEntry>,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.events.set_playlist(playlist)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn next_event(&self) -> Result<(), DashboardControllerError> {
+        Ok(self.events.next_event()?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn get_event_catalog(&self) -> Result<Vec<CatalogEvent>, DashboardControllerError> {
+        Ok(self.events.catalog()?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn save_catalog_event(&self, event: CatalogEvent) -> Result<(), DashboardControllerError> {
+        Ok(self.events.save_catalog_event(event)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn remove_catalog_event(&self, ts: u64) -> Result<(), DashboardControllerError> {
+        Ok(self.events.remove_catalog_event(ts)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn get_client_config(&self) -> Result<Vec<ConfigEntry>, DashboardControllerError> {
+        Ok(self.client_config.entries()?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_client_config_item(
+        &self,
+        name: String,
+        value: Option<String>,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.client_config.set_item(name, value)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn get_gameplay_config(
+        &self,
+        event: Option<u64>,
+    ) -> Result<Vec<ConfigEntry>, DashboardControllerError> {
+        Ok(self.gameplay_config.entries(event)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_gameplay_config_item(
+        &self,
+        event: Option<u64>,
+        name: String,
+        value: Option<String>,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.gameplay_config.set_item(event, name, value)?)
+    }
+
+    #[instrument(skip(self))]
+    pub fn get_client_versions(&self) -> ClientVersionsResponse {
+        ClientVersionsResponse {
+            policy: self.client_versions.policy(),
+            seen: self.client_versions.seen(),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_client_version_policy(
+        &self,
+        policy: ClientVersionPolicy,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.client_versions.set_policy(policy)?)
+    }
+
     #[instrument]
-    pub fn set_event(_ts: u64) -> Result<(), DashboardControllerError> {
-        Ok(())
+    pub fn reload_config() -> Result<ConfigReloadResponse, DashboardControllerError> {
+        Ok(crate::config::reload()?)
     }
 
     #[instrument]
@@ -102,28 +366,19 @@ impl DashboardController {
 
     #[instrument]
     pub fn set_dlc_path(dlc_path: String) -> Result<(), DashboardControllerError> {
-        let mut path = if OPTIONS.take().portable {
-            relative_path().map_err(anyhow::Error::from)?
-        } else {
-            DIRECTORIES.config_local_dir().to_path_buf()
-        };
-        path.push("server.toml");
+        let path = config_path("server.toml").map_err(anyhow::Error::from)?;
 
-        let mut server_options = OPTIONS.take();
-        server_options.dlc_folder = dlc_path;
-        server_options.save(path)?;
+        // the live config keeps the old value until the server is restarted
+        OPTIONS
+            .take()
+            .save_with(path, |server_options| server_options.dlc_folder = dlc_path)?;
 
         Ok(())
     }
 
     #[instrument]
     pub fn set_default_donuts(donuts: u32) -> Result<(), DashboardControllerError> {
-        let mut path = if OPTIONS.take().portable {
-            relative_path().map_err(anyhow::Error::from)?
-        } else {
-            DIRECTORIES.config_local_dir().to_path_buf()
-        };
-        path.push("server.toml");
+        let path = config_path("server.toml").map_err(anyhow::Error::from)?;
 
         let mut server_options = OPTIONS.take();
         server_options.default_donuts = donuts;
@@ -134,28 +389,19 @@ impl DashboardController {
 
     #[instrument]
     pub fn set_port(port: u16) -> Result<(), DashboardControllerError> {
-        let mut path = if OPTIONS.take().portable {
-            relative_path().map_err(anyhow::Error::from)?
-        } else {
-            DIRECTORIES.config_local_dir().to_path_buf()
-        };
-        path.push("server.toml");
+        let path = config_path("server.toml").map_err(anyhow::Error::from)?;
 
-        let mut server_options = OPTIONS.take();
-        server_options.port = port;
-        server_options.save(path)?;
+        // the live config keeps the old value until the server is restarted
+        OPTIONS
+            .take()
+            .save_with(path, |server_options| server_options.port = port)?;
 
         Ok(())
     }
 
     #[instrument]
     pub fn set_address(address: String) -> Result<(), DashboardControllerError> {
-        let mut path = if OPTIONS.take().portable {
-            relative_path().map_err(anyhow::Error::from)?
-        } else {
-            DIRECTORIES.config_local_dir().to_path_buf()
-        };
-        path.push("server.toml");
+        let path = config_path("server.toml").map_err(anyhow::Error::from)?;
 
         let mut server_options = OPTIONS.take();
         server_options.server_address = address;
@@ -169,6 +415,26 @@ impl DashboardController {
         Ok(MayhemController::get_lobby_time()?)
     }
 
+    #[instrument]
+    pub fn get_lobby_clock() -> Result<LobbyClockResponse, DashboardControllerError> {
+        let clock = OPTIONS.take().lobby_clock.clone();
+
+        Ok(LobbyClockResponse {
+            time: MayhemController::get_lobby_time()?,
+            frozen: clock.frozen_at.is_some(),
+            offset: clock.offset,
+        })
+    }
+
+    #[instrument(skip(self))]
+    pub fn set_lobby_clock(
+        &self,
+        frozen: bool,
+        offset: i64,
+    ) -> Result<(), DashboardControllerError> {
+        Ok(self.events.set_lobby_clock(frozen, offset)?)
+    }
+
     #[instrument]
     pub async fn set_password(
         &self,
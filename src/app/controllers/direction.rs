@@ -1,31 +1,132 @@
-use crate::{app::models::direction::Direction, config::DIRECTIONS};
+use super::client_version::ClientVersionController;
+use crate::{
+    app::models::{
+        dashboard::VersionStatus,
+        direction::{Direction, DirectionProfile, KVPair},
+    },
+    config::{server_url, DIRECTIONS, OPTIONS},
+};
+use axum::http::{header::HOST, HeaderMap};
 use tracing::{debug, instrument};
 
 #[derive(Debug, Default, Clone)]
 pub struct DirectionController;
 
 impl DirectionController {
-    #[instrument]
-    pub async fn by_package(platform: &String, package_id: &String) -> Direction {
+    #[instrument(skip(headers))]
+    pub async fn by_package(
+        platform: &String,
+        package_id: &String,
+        headers: &HeaderMap,
+    ) -> Direction {
         debug!("direction for {package_id}({platform}) requested");
 
-        Self::direction(platform).await
+        Self::direction(platform, package_id, headers).await
     }
 
-    #[instrument]
-    pub async fn by_bundle(platform: &String, bundle_id: &String) -> Direction {
+    #[instrument(skip(headers))]
+    pub async fn by_bundle(
+        platform: &String,
+        bundle_id: &String,
+        headers: &HeaderMap,
+    ) -> Direction {
         debug!("direction for {bundle_id}({platform}) requested");
 
-        Self::direction(platform).await
+        Self::direction(platform, bundle_id, headers).await
     }
 
-    #[instrument]
-    async fn direction(platform: &String) -> Direction {
-        let mut dir = DIRECTIONS.clone();
+    /// `package_id` is the package id on android and the bundle id on ios
+    #[instrument(skip(headers))]
+    async fn direction(platform: &String, package_id: &String, headers: &HeaderMap) -> Direction {
+        let mut dir = DIRECTIONS.take().clone();
 
         dir.client_id = format!("simpsons4-{platform}-client");
         dir.mdm_app_key = format!("simpsons4-{platform}");
 
+        let profile = DirectionProfile::select(&dir.profiles, platform, package_id).cloned();
+
+        if let Some(profile) = profile {
+            debug!(
+                "using direction profile of {:?}({:?})",
+                profile.package_id, profile.platform
+            );
+
+            dir.client_id = profile.client_id.unwrap_or(dir.client_id);
+            dir.mdm_app_key = profile.mdm_app_key.unwrap_or(dir.mdm_app_key);
+            dir.disabled_features = profile.disabled_features.unwrap_or(dir.disabled_features);
+            Self::merge(&mut dir.poll_intervals, profile.poll_intervals);
+            Self::merge(&mut dir.server_data, profile.server_data);
+        }
+
+        if let Some(version) = ClientVersionController::version(headers) {
+            dir.app_upgrade =
+                match ClientVersionController::default().check(Some(platform), &version) {
+                    VersionStatus::Allowed => 0,
+                    VersionStatus::UpgradeAvailable => 1,
+                    VersionStatus::Rejected => 2,
+                };
+        }
+
+        if let Some(url) = Self::request_url(headers) {
+            let configured = server_url(&OPTIONS.take());
+
+            // only the addresses which were filled in from the server config follow the request
+            dir.server_data
+                .iter_mut()
+                .filter(|kv| kv.value == configured)
+                .for_each(|kv| kv.value = url.clone());
+        }
+
         dir
     }
+
+    /// replaces the values of `pairs` by key with the ones of `overrides`
+    fn merge(pairs: &mut Vec<KVPair>, overrides: Option<Vec<KVPair>>) {
+        for kv in overrides.into_iter().flatten() {
+            match pairs.iter_mut().find(|pair| pair.key == kv.key) {
+                Some(pair) => pair.value = kv.value,
+                None => pairs.push(kv),
+            }
+        }
+    }
+
+    /// base url the request was sent to, if its host is one of the `allowed_hosts`.
+    ///
+    /// `X-Forwarded-Host` and `X-Forwarded-Proto` of a reverse proxy take precedence over `Host`
+    fn request_url(headers: &HeaderMap) -> Option<String> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                // proxies append their value to a list
+                .and_then(|value| value.split(',').next())
+                .map(|value| value.trim().to_ascii_lowercase())
+                .filter(|value| !value.is_empty())
+        };
+
+        let host = header("x-forwarded-host").or_else(|| header(HOST.as_str()))?;
+        let options = OPTIONS.take();
+
+        let allowed = options.allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+
+            // hosts listed without a port match any port
+            allowed == host
+                || host
+                    .rsplit_once(':')
+                    .is_some_and(|(hostname, _)| hostname == allowed)
+        });
+        if !allowed {
+            return None;
+        }
+
+        let scheme = match header("x-forwarded-proto").as_deref() {
+            Some("https") => "https",
+            Some("http") => "http",
+            _ if options.server_address.starts_with("https://") => "https",
+            _ => "http",
+        };
+
+        Some(format!("{scheme}://{host}/"))
+    }
 }
@@ -0,0 +1,175 @@
+use crate::{
+    app::{
+        controllers::events::{EventController, EventControllerError},
+        models::dashboard::EventOverride,
+    },
+    config::{LobbyClock, OPTIONS},
+    database::Database,
+    util::millis_from_unix_epoch,
+};
+use tracing::{debug, instrument};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EventOverrideControllerError {
+    #[error("No event could be found with that timestamp")]
+    NotFound,
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+    #[error(transparent)]
+    Events(#[from] EventControllerError),
+}
+
+/// event and lobby clock of players which do not follow the global event
+#[derive(Debug, Clone)]
+pub struct EventOverrideController {
+    db: Database,
+}
+
+impl Default for EventOverrideController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct EventOverrideRow {
+    event: i64,
+    selected_at: i64,
+    frozen_at: Option<i64>,
+    paused: i64,
+    clock_offset: i64,
+}
+
+impl EventOverrideRow {
+    fn clock(&self) -> LobbyClock {
+        LobbyClock {
+            selected_at: self.selected_at as u64,
+            frozen_at: self.frozen_at.map(|frozen_at| frozen_at as u64),
+            paused: self.paused as u64,
+            offset: self.clock_offset,
+        }
+    }
+}
+
+impl EventOverrideController {
+    /// returns the override of `mayhem_id` if there is one
+    #[instrument(skip(self))]
+    pub async fn get(
+        &self,
+        mayhem_id: &str,
+    ) -> Result<Option<EventOverride>, EventOverrideControllerError> {
+        Ok(self.row(mayhem_id).await?.map(|row| EventOverride {
+            event: row.event as u64,
+            frozen: row.frozen_at.is_some(),
+            offset: row.clock_offset,
+        }))
+    }
+
+    /// keeps `mayhem_id` in its own event. the clock restarts if the event changed
+    #[instrument(skip(self))]
+    pub async fn set(
+        &self,
+        mayhem_id: &str,
+        event_override: &EventOverride,
+    ) -> Result<(), EventOverrideControllerError> {
+        const QUERY: &str = r#"
+            INSERT INTO event_overrides (mayhem_id, event, selected_at, frozen_at, paused, clock_offset)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(mayhem_id) DO UPDATE SET
+                event = excluded.event,
+                selected_at = excluded.selected_at,
+                frozen_at = excluded.frozen_at,
+                paused = excluded.paused,
+                clock_offset = excluded.clock_offset"#;
+
+        if EventController::default()
+            .find(event_override.event)?
+            .is_none()
+        {
+            return Err(EventOverrideControllerError::NotFound);
+        }
+
+        let mut clock = match self.row(mayhem_id).await? {
+            Some(row) if row.event as u64 == event_override.event => row.clock(),
+            Some(row) => {
+                let mut clock = row.clock();
+                clock.select()?;
+
+                clock
+            }
+            None => LobbyClock {
+                selected_at: millis_from_unix_epoch()? as u64,
+                ..Default::default()
+            },
+        };
+        clock.freeze(event_override.frozen)?;
+        clock.offset = event_override.offset;
+
+        sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(event_override.event as i64)
+            .bind(clock.selected_at as i64)
+            .bind(clock.frozen_at.map(|frozen_at| frozen_at as i64))
+            .bind(clock.paused as i64)
+            .bind(clock.offset)
+            .execute(&self.db)
+            .await?;
+
+        debug!("{mayhem_id} stays in event {}", event_override.event);
+
+        Ok(())
+    }
+
+    /// lets `mayhem_id` follow the global event again
+    #[instrument(skip(self))]
+    pub async fn remove(&self, mayhem_id: &str) -> Result<bool, EventOverrideControllerError> {
+        const QUERY: &str = "DELETE FROM event_overrides WHERE mayhem_id = ?";
+
+        Ok(sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected()
+            > 0)
+    }
+
+    /// returns the event `mayhem_id` plays in
+    #[instrument(skip(self))]
+    pub async fn event(&self, mayhem_id: &str) -> Result<u64, EventOverrideControllerError> {
+        match self.row(mayhem_id).await? {
+            Some(row) => Ok(row.event as u64),
+            None => Ok(OPTIONS.take().current_event),
+        }
+    }
+
+    /// returns the lobby time of `mayhem_id` in milliseconds
+    #[instrument(skip(self))]
+    pub async fn lobby_time(&self, mayhem_id: &str) -> Result<u128, EventOverrideControllerError> {
+        match self.row(mayhem_id).await? {
+            Some(row) => Ok(row.clock().now(row.event as u64)?),
+            None => Ok(OPTIONS.take().lobby_time()?),
+        }
+    }
+
+    async fn row(
+        &self,
+        mayhem_id: &str,
+    ) -> Result<Option<EventOverrideRow>, EventOverrideControllerError> {
+        const QUERY: &str = r#"
+            SELECT event, selected_at, frozen_at, paused, clock_offset
+            FROM event_overrides
+            WHERE mayhem_id = ?"#;
+
+        Ok(sqlx::query_as::<_, EventOverrideRow>(QUERY)
+            .bind(mayhem_id)
+            .fetch_optional(&self.db)
+            .await?)
+    }
+}
@@ -1,6 +1,18 @@
-// use crate::database::Database;
-use crate::{json_error, util::error::ErrorMessage};
-use tracing::{error, instrument, warn};
+use crate::{
+    app::models::{
+        dashboard::{CatalogEvent, PlaylistEntry, ScheduledEvent},
+        events::{EventCatalog, Playlist},
+    },
+    config::OPTIONS,
+    json_error,
+    util::{config_path, error::ErrorMessage, secs_from_unix_epoch, CachedToml},
+};
+use std::time::Duration;
+use tracing::{error, info, instrument, warn};
+
+/// a missing file means the built-in events
+static CATALOG: CachedToml<EventCatalog> =
+    CachedToml::validated("events.toml", EventCatalog::builtin, EventCatalog::validate);
 
 #[derive(Debug, thiserror::Error)]
 pub enum EventControllerError {
@@ -8,13 +20,28 @@ pub enum EventControllerError {
     NotFound,
     #[error("failed to execute db query")]
     Database(#[from] sqlx::Error),
+    #[error("failed to save server config")]
+    Config(#[from] anyhow::Error),
+    #[error("failed to parse playlist")]
+    Playlist(#[from] toml::de::Error),
+    #[error("invalid event catalog: {0}")]
+    InvalidCatalog(String),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
 }
 
 impl From<EventControllerError> for u16 {
     fn from(value: EventControllerError) -> Self {
         match value {
             EventControllerError::NotFound => 404,
-            EventControllerError::Database(_) => 500,
+            EventControllerError::InvalidCatalog(_) => 400,
+            EventControllerError::Database(_)
+            | EventControllerError::Config(_)
+            | EventControllerError::Playlist(_)
+            | EventControllerError::IO(_)
+            | EventControllerError::Time(_) => 500,
         }
     }
 }
@@ -24,8 +51,14 @@ impl From<EventControllerError> for ErrorMessage {
         tracing::error!("{value}");
 
         match value {
-            EventControllerError::NotFound => json_error!(value.into(), format!("{value}")),
-            EventControllerError::Database(_) => json_error!(),
+            EventControllerError::NotFound | EventControllerError::InvalidCatalog(_) => {
+                json_error!(value.into(), format!("{value}"))
+            }
+            EventControllerError::Database(_)
+            | EventControllerError::Config(_)
+            | EventControllerError::Playlist(_)
+            | EventControllerError::IO(_)
+            | EventControllerError::Time(_) => json_error!(),
         }
     }
 }
@@ -40,15 +73,292 @@ impl From<EventControllerError> for axum::Json<ErrorMessage> {
 pub struct EventController {}
 
 impl EventController {
+    /// selects the event starting at `ts` and persists it in `server.toml`.
+    ///
+    /// the lobby clock restarts at the start of the event
     #[instrument(skip(self))]
     pub fn set_event(&self, ts: u64) -> Result<(), EventControllerError> {
+        let Some(event) = self.find(ts)? else {
+            return Err(EventControllerError::NotFound);
+        };
+
+        let path = config_path("server.toml")?;
+        let mut server_options = OPTIONS.take();
+        server_options.current_event = ts;
+        server_options.lobby_clock.select()?;
+        server_options.save(path)?;
+
+        info!("selected event {}", event.name);
+
         Ok(())
     }
 
+    /// returns start timestamp and name of the selected event
     #[instrument(skip(self))]
     pub fn get_event(&self) -> Result<(u64, String), EventControllerError> {
-        let event = crate::app::models::events::TSTO_EVENTS[0];
+        let ts = OPTIONS.take().current_event;
+
+        let catalog = self.catalog()?;
+
+        let event = match catalog.iter().find(|event| event.start == ts) {
+            Some(event) => event,
+            None => {
+                warn!("configured event {ts} is unknown, falling back to normal play");
+
+                catalog
+                    .iter()
+                    .find(|event| event.start == 0)
+                    .ok_or(EventControllerError::NotFound)?
+            }
+        };
+
+        Ok((event.start, event.name.clone()))
+    }
+
+    /// returns the events of `events.toml`, or the built-in events if there is no catalog
+    #[instrument(skip(self))]
+    pub fn catalog(&self) -> Result<Vec<CatalogEvent>, EventControllerError> {
+        Ok(CATALOG.load()?.events)
+    }
+
+    /// returns the event of the catalog starting at `ts`
+    #[instrument(skip(self))]
+    pub fn find(&self, ts: u64) -> Result<Option<CatalogEvent>, EventControllerError> {
+        Ok(self.catalog()?.into_iter().find(|event| event.start == ts))
+    }
+
+    /// adds `event` to the catalog or replaces the event with the same start
+    #[instrument(skip(self))]
+    pub fn save_catalog_event(&self, event: CatalogEvent) -> Result<(), EventControllerError> {
+        let mut catalog = CATALOG.load()?;
+
+        match catalog.events.iter_mut().find(|e| e.start == event.start) {
+            Some(existing) => *existing = event,
+            None => {
+                catalog.events.push(event);
+                catalog.events.sort_by_key(|event| event.start);
+            }
+        }
+
+        Self::write_catalog(&catalog)
+    }
+
+    /// removes the event starting at `ts` from the catalog
+    #[instrument(skip(self))]
+    pub fn remove_catalog_event(&self, ts: u64) -> Result<(), EventControllerError> {
+        let mut catalog = CATALOG.load()?;
+        let len = catalog.events.len();
+        catalog.events.retain(|event| event.start != ts);
+
+        if catalog.events.len() == len {
+            return Err(EventControllerError::NotFound);
+        }
+
+        Self::write_catalog(&catalog)
+    }
+
+    /// freezes or resumes the lobby clock and sets its offset in seconds
+    #[instrument(skip(self))]
+    pub fn set_lobby_clock(&self, frozen: bool, offset: i64) -> Result<(), EventControllerError> {
+        let path = config_path("server.toml")?;
+        let mut server_options = OPTIONS.take();
+        server_options.lobby_clock.freeze(frozen)?;
+        server_options.lobby_clock.offset = offset;
+        server_options.save(path)?;
+
+        Ok(())
+    }
+
+    /// returns the entries of `playlist.toml`, or every event after normal play if there is no playlist
+    #[instrument(skip(self))]
+    pub fn playlist(&self) -> Result<Vec<PlaylistEntry>, EventControllerError> {
+        match std::fs::read_to_string(config_path("playlist.toml")?) {
+            Ok(content) => Ok(toml::from_str::<Playlist>(&content)?.events),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(self
+                .catalog()?
+                .iter()
+                .filter(|event| event.start != 0)
+                .map(|event| PlaylistEntry {
+                    event: event.start,
+                    duration: None,
+                })
+                .collect()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// replaces `playlist.toml`
+    #[instrument(skip(self))]
+    pub fn set_playlist(&self, events: Vec<PlaylistEntry>) -> Result<(), EventControllerError> {
+        let catalog = self.catalog()?;
+
+        if events
+            .iter()
+            .any(|entry| !catalog.iter().any(|event| event.start == entry.event))
+        {
+            return Err(EventControllerError::NotFound);
+        }
+
+        let content = toml::to_string_pretty(&Playlist { events }).map_err(anyhow::Error::from)?;
+        std::fs::write(config_path("playlist.toml")?, content)?;
+
+        Ok(())
+    }
+
+    /// enables or disables the rotation and sets the default duration of an event in seconds.
+    ///
+    /// an enabled rotation switches to the next event once `interval` passed
+    #[instrument(skip(self))]
+    pub fn set_rotation(&self, enabled: bool, interval: u64) -> Result<(), EventControllerError> {
+        let current_event = OPTIONS.take().current_event;
+        let duration = match self
+            .playlist()?
+            .iter()
+            .find(|entry| entry.event == current_event)
+        {
+            Some(entry) => self.duration(entry)?,
+            None => None,
+        };
+
+        let path = config_path("server.toml")?;
+        let now = secs_from_unix_epoch()?;
+
+        let mut server_options = OPTIONS.take();
+        let rotation = &mut server_options.event_rotation;
+        if enabled && !rotation.enabled {
+            rotation.next_switch = now + interval;
+        } else if enabled && interval != rotation.interval && duration.is_none() {
+            // the current event keeps the time it got selected at
+            let selected_at = rotation.next_switch.saturating_sub(rotation.interval);
+            rotation.next_switch = now.max(selected_at + interval);
+        }
+        rotation.enabled = enabled;
+        rotation.interval = interval;
+        server_options.save(path)?;
+
+        Ok(())
+    }
+
+    /// lists the next `count` events of the rotation with the time they get selected at
+    #[instrument(skip(self))]
+    pub fn schedule(&self, count: usize) -> Result<Vec<ScheduledEvent>, EventControllerError> {
+        let playlist = self.playlist()?;
+        let catalog = self.catalog()?;
+        let (rotation, current_event) = {
+            let options = OPTIONS.take();
+
+            (options.event_rotation.clone(), options.current_event)
+        };
+
+        if !rotation.enabled || playlist.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut starts_at = rotation.next_switch.max(secs_from_unix_epoch()?);
+
+        Ok(playlist
+            .iter()
+            .cycle()
+            .skip(Self::next_index(&playlist, current_event))
+            .take(count)
+            .map(|entry| {
+                let event = catalog.iter().find(|event| event.start == entry.event);
+                let scheduled = ScheduledEvent {
+                    event: entry.event,
+                    name: event.map_or_else(|| entry.event.to_string(), |e| e.name.clone()),
+                    starts_at,
+                };
+                starts_at += entry
+                    .duration
+                    .or(event.and_then(|e| e.duration))
+                    .unwrap_or(rotation.interval);
+
+                scheduled
+            })
+            .collect())
+    }
+
+    /// selects the next event of the playlist right away
+    #[instrument(skip(self))]
+    pub fn next_event(&self) -> Result<(), EventControllerError> {
+        let playlist = self.playlist()?;
+        let current_event = OPTIONS.take().current_event;
+
+        if playlist.is_empty() {
+            warn!("playlist is empty, there is no event to switch to");
+
+            return Ok(());
+        }
+
+        let entry = &playlist[Self::next_index(&playlist, current_event)];
+        let duration = self.duration(entry)?;
+        self.set_event(entry.event)?;
+
+        let path = config_path("server.toml")?;
+        let now = secs_from_unix_epoch()?;
+
+        let mut server_options = OPTIONS.take();
+        let rotation = &mut server_options.event_rotation;
+        rotation.next_switch = now + duration.unwrap_or(rotation.interval);
+        server_options.save(path)?;
+
+        Ok(())
+    }
+
+    /// switches to the next event of the playlist if the current one is due
+    #[instrument(skip(self))]
+    pub fn rotate(&self) -> Result<(), EventControllerError> {
+        let rotation = OPTIONS.take().event_rotation.clone();
+
+        if rotation.enabled && rotation.next_switch <= secs_from_unix_epoch()? {
+            self.next_event()?;
+        }
+
+        Ok(())
+    }
+
+    /// checks every minute whether the next event of the rotation is due
+    pub async fn run_rotation(self) {
+        const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+        loop {
+            if let Err(e) = self.rotate() {
+                error!("failed to rotate event: {e}");
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    /// seconds `entry` stays selected, `None` if it follows the interval of the rotation
+    fn duration(&self, entry: &PlaylistEntry) -> Result<Option<u64>, EventControllerError> {
+        Ok(entry
+            .duration
+            .or(self.find(entry.event)?.and_then(|event| event.duration)))
+    }
+
+    /// index of the playlist entry after `current_event`.
+    ///
+    /// if the event is not in the playlist anymore, the rotation continues with the next later event
+    fn next_index(playlist: &[PlaylistEntry], current_event: u64) -> usize {
+        match playlist
+            .iter()
+            .position(|entry| entry.event == current_event)
+        {
+            Some(index) => (index + 1) % playlist.len(),
+            None => playlist
+                .iter()
+                .position(|entry| entry.event > current_event)
+                .unwrap_or(0),
+        }
+    }
+
+    /// validates `catalog` and replaces `events.toml` with it
+    fn write_catalog(catalog: &EventCatalog) -> Result<(), EventControllerError> {
+        catalog
+            .validate()
+            .map_err(EventControllerError::InvalidCatalog)?;
 
-        Ok((event.0, event.1.to_owned()))
+        Ok(CATALOG.save::<anyhow::Error>(catalog)?)
     }
 }
@@ -0,0 +1,401 @@
+use crate::{
+    app::models::friend::{Friend, FriendsResponse},
+    database::Database,
+    json_error,
+    util::{error::ErrorMessage, millis_from_unix_epoch},
+};
+use tracing::{debug, instrument, warn};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FriendControllerError {
+    #[error("Invalid AccessToken")]
+    InvalidAccessToken,
+    #[error("No user could be found with that MayhemId")]
+    NotFound,
+    #[error("No user could be found with that friend code")]
+    InvalidCode,
+    #[error("Users can not befriend themselves")]
+    SelfFriendship,
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+impl From<FriendControllerError> for u16 {
+    fn from(value: FriendControllerError) -> Self {
+        match value {
+            FriendControllerError::InvalidAccessToken => 401,
+            FriendControllerError::NotFound | FriendControllerError::InvalidCode => 404,
+            FriendControllerError::SelfFriendship => 400,
+            FriendControllerError::DatabaseError(_) | FriendControllerError::Time(_) => 500,
+        }
+    }
+}
+
+impl From<FriendControllerError> for ErrorMessage {
+    fn from(value: FriendControllerError) -> ErrorMessage {
+        match value {
+            FriendControllerError::InvalidAccessToken
+            | FriendControllerError::NotFound
+            | FriendControllerError::InvalidCode
+            | FriendControllerError::SelfFriendship => {
+                warn!("{value}");
+
+                json_error!(value.into(), format!("{value}"))
+            }
+            FriendControllerError::DatabaseError(_) | FriendControllerError::Time(_) => {
+                tracing::error!("{value}");
+
+                json_error!()
+            }
+        }
+    }
+}
+
+impl From<FriendControllerError> for axum::Json<ErrorMessage> {
+    fn from(value: FriendControllerError) -> Self {
+        axum::Json(value.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FriendController {
+    db: Database,
+}
+
+impl Default for FriendController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+impl FriendController {
+    /// characters of friend codes, leaving out the ones which are easily mistaken for each other
+    const CODE_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    const CODE_LENGTH: usize = 8;
+
+    /// resolves the mayhem id of the user owning `access_token`
+    #[instrument(skip(self))]
+    pub async fn authenticate(&self, access_token: &str) -> Result<String, FriendControllerError> {
+        const QUERY: &str = "SELECT mayhem_id FROM users WHERE user_access_token = ?";
+
+        sqlx::query_scalar::<_, u64>(QUERY)
+            .bind(access_token)
+            .fetch_optional(&self.db)
+            .await?
+            .map(|mayhem_id| mayhem_id.to_string())
+            .ok_or(FriendControllerError::InvalidAccessToken)
+    }
+
+    /// returns the friend code of `mayhem_id`, creating one if it has none yet
+    #[instrument(skip(self))]
+    pub async fn code(&self, mayhem_id: &str) -> Result<String, FriendControllerError> {
+        const QUERY: &str = "SELECT code FROM friend_codes WHERE mayhem_id = ?";
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_optional(&self.db)
+            .await?
+        {
+            Some(code) => Ok(code),
+            None => self.regenerate_code(mayhem_id).await,
+        }
+    }
+
+    /// replaces the friend code of `mayhem_id`, so the previous code can not be redeemed anymore
+    #[instrument(skip(self))]
+    pub async fn regenerate_code(&self, mayhem_id: &str) -> Result<String, FriendControllerError> {
+        const QUERY_USER: &str = "SELECT COUNT(*) FROM users WHERE mayhem_id = ?";
+        const QUERY: &str = r#"
+            INSERT INTO friend_codes (mayhem_id, code, created_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(mayhem_id) DO UPDATE SET
+                code = excluded.code,
+                created_at = excluded.created_at"#;
+
+        if sqlx::query_scalar::<_, i64>(QUERY_USER)
+            .bind(mayhem_id)
+            .fetch_one(&self.db)
+            .await?
+            == 0
+        {
+            return Err(FriendControllerError::NotFound);
+        }
+
+        loop {
+            let code = (0..Self::CODE_LENGTH)
+                .map(|_| {
+                    Self::CODE_CHARSET[rand::random_range(0..Self::CODE_CHARSET.len())] as char
+                })
+                .collect::<String>();
+
+            match sqlx::query(QUERY)
+                .bind(mayhem_id)
+                .bind(&code)
+                .bind(millis_from_unix_epoch()? as i64)
+                .execute(&self.db)
+                .await
+            {
+                Ok(_) => {
+                    debug!("created friend code for {mayhem_id}");
+
+                    return Ok(code);
+                }
+                // the code is taken by another user already
+                Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                    warn!("friend code collision, retrying");
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// befriends `mayhem_id` with the owner of `code` right away. returns the mayhem id of the owner
+    #[instrument(skip(self))]
+    pub async fn redeem(
+        &self,
+        mayhem_id: &str,
+        code: &str,
+    ) -> Result<String, FriendControllerError> {
+        const QUERY: &str = "SELECT mayhem_id FROM friend_codes WHERE code = ?";
+        const INSERT_QUERY: &str = r#"
+            INSERT INTO friendships (mayhem_id, friend_id, accepted, created_at)
+            VALUES (?, ?, 1, ?), (?, ?, 1, ?)
+            ON CONFLICT(mayhem_id, friend_id) DO UPDATE SET accepted = 1"#;
+
+        let friend_id = sqlx::query_scalar::<_, String>(QUERY)
+            .bind(code.trim().to_uppercase())
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or(FriendControllerError::InvalidCode)?;
+
+        if friend_id == mayhem_id {
+            return Err(FriendControllerError::SelfFriendship);
+        }
+
+        let now = millis_from_unix_epoch()? as i64;
+
+        sqlx::query(INSERT_QUERY)
+            .bind(mayhem_id)
+            .bind(&friend_id)
+            .bind(now)
+            .bind(&friend_id)
+            .bind(mayhem_id)
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+
+        debug!("{mayhem_id} redeemed the friend code of {friend_id}");
+
+        Ok(friend_id)
+    }
+
+    /// lists friends and pending requests of `mayhem_id`
+    #[instrument(skip(self))]
+    pub async fn list(&self, mayhem_id: &str) -> Result<FriendsResponse, FriendControllerError> {
+        Ok(FriendsResponse {
+            friends: self.friends(mayhem_id).await?,
+            requests: self.requests(mayhem_id).await?,
+            sent_requests: self.sent_requests(mayhem_id).await?,
+        })
+    }
+
+    /// sends a friend request from `mayhem_id` to `friend_id`.
+    ///
+    /// if `friend_id` already requested `mayhem_id` both become friends right away.
+    /// returns whether they are friends afterwards
+    #[instrument(skip(self))]
+    pub async fn request(
+        &self,
+        mayhem_id: &str,
+        friend_id: &str,
+    ) -> Result<bool, FriendControllerError> {
+        const QUERY_USER: &str = "SELECT COUNT(*) FROM users WHERE mayhem_id = ?";
+        const QUERY: &str = r#"
+            SELECT accepted FROM friendships
+            WHERE mayhem_id = ? AND friend_id = ?"#;
+        const INSERT_QUERY: &str = r#"
+            INSERT INTO friendships (mayhem_id, friend_id, accepted, created_at)
+            VALUES (?, ?, 0, ?)"#;
+
+        if mayhem_id == friend_id {
+            return Err(FriendControllerError::SelfFriendship);
+        }
+
+        if sqlx::query_scalar::<_, i64>(QUERY_USER)
+            .bind(friend_id)
+            .fetch_one(&self.db)
+            .await?
+            == 0
+        {
+            return Err(FriendControllerError::NotFound);
+        }
+
+        if let Some(accepted) = sqlx::query_scalar::<_, bool>(QUERY)
+            .bind(mayhem_id)
+            .bind(friend_id)
+            .fetch_optional(&self.db)
+            .await?
+        {
+            return Ok(accepted);
+        }
+
+        if self.accept(mayhem_id, friend_id).await? {
+            return Ok(true);
+        }
+
+        sqlx::query(INSERT_QUERY)
+            .bind(mayhem_id)
+            .bind(friend_id)
+            .bind(millis_from_unix_epoch()? as i64)
+            .execute(&self.db)
+            .await?;
+
+        debug!("{mayhem_id} sent a friend request to {friend_id}");
+
+        Ok(false)
+    }
+
+    /// accepts the pending request `requester` sent to `mayhem_id`. returns `false` if there is none
+    #[instrument(skip(self))]
+    pub async fn accept(
+        &self,
+        mayhem_id: &str,
+        requester: &str,
+    ) -> Result<bool, FriendControllerError> {
+        const UPDATE_QUERY: &str = r#"
+            UPDATE friendships
+            SET accepted = 1
+            WHERE mayhem_id = ? AND friend_id = ? AND accepted = 0"#;
+        const INSERT_QUERY: &str = r#"
+            INSERT INTO friendships (mayhem_id, friend_id, accepted, created_at)
+            VALUES (?, ?, 1, ?)
+            ON CONFLICT(mayhem_id, friend_id) DO UPDATE SET accepted = 1"#;
+
+        let mut tx = self.db.begin().await?;
+
+        if sqlx::query(UPDATE_QUERY)
+            .bind(requester)
+            .bind(mayhem_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            == 0
+        {
+            return Ok(false);
+        }
+
+        sqlx::query(INSERT_QUERY)
+            .bind(mayhem_id)
+            .bind(requester)
+            .bind(millis_from_unix_epoch()? as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        debug!("{mayhem_id} accepted the friend request of {requester}");
+
+        Ok(true)
+    }
+
+    /// removes the friendship between `mayhem_id` and `friend_id` as well as pending requests in both directions
+    #[instrument(skip(self))]
+    pub async fn remove(
+        &self,
+        mayhem_id: &str,
+        friend_id: &str,
+    ) -> Result<bool, FriendControllerError> {
+        const QUERY: &str = r#"
+            DELETE FROM friendships
+            WHERE (mayhem_id = ? AND friend_id = ?) OR (mayhem_id = ? AND friend_id = ?)"#;
+
+        Ok(sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(friend_id)
+            .bind(friend_id)
+            .bind(mayhem_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected()
+            > 0)
+    }
+
+    /// whether `mayhem_id` and `friend_id` are friends
+    #[instrument(skip(self))]
+    pub async fn are_friends(
+        &self,
+        mayhem_id: &str,
+        friend_id: &str,
+    ) -> Result<bool, FriendControllerError> {
+        const QUERY: &str = r#"
+            SELECT COUNT(*) FROM friendships
+            WHERE mayhem_id = ? AND friend_id = ? AND accepted = 1"#;
+
+        Ok(sqlx::query_scalar::<_, i64>(QUERY)
+            .bind(mayhem_id)
+            .bind(friend_id)
+            .fetch_one(&self.db)
+            .await?
+            > 0)
+    }
+
+    /// lists all friends of `mayhem_id`
+    #[instrument(skip(self))]
+    pub async fn friends(&self, mayhem_id: &str) -> Result<Vec<Friend>, FriendControllerError> {
+        const QUERY: &str = r#"
+            SELECT f.friend_id, u.user_name FROM friendships f
+            LEFT JOIN users u ON u.mayhem_id = f.friend_id
+            WHERE f.mayhem_id = ? AND f.accepted = 1
+            ORDER BY f.created_at ASC"#;
+
+        self.query_friends(QUERY, mayhem_id).await
+    }
+
+    /// lists users waiting for `mayhem_id` to accept their request
+    #[instrument(skip(self))]
+    pub async fn requests(&self, mayhem_id: &str) -> Result<Vec<Friend>, FriendControllerError> {
+        const QUERY: &str = r#"
+            SELECT f.mayhem_id, u.user_name FROM friendships f
+            LEFT JOIN users u ON u.mayhem_id = f.mayhem_id
+            WHERE f.friend_id = ? AND f.accepted = 0
+            ORDER BY f.created_at ASC"#;
+
+        self.query_friends(QUERY, mayhem_id).await
+    }
+
+    /// lists users `mayhem_id` sent a request which is not accepted yet
+    #[instrument(skip(self))]
+    pub async fn sent_requests(
+        &self,
+        mayhem_id: &str,
+    ) -> Result<Vec<Friend>, FriendControllerError> {
+        const QUERY: &str = r#"
+            SELECT f.friend_id, u.user_name FROM friendships f
+            LEFT JOIN users u ON u.mayhem_id = f.friend_id
+            WHERE f.mayhem_id = ? AND f.accepted = 0
+            ORDER BY f.created_at ASC"#;
+
+        self.query_friends(QUERY, mayhem_id).await
+    }
+
+    async fn query_friends(
+        &self,
+        query: &'static str,
+        mayhem_id: &str,
+    ) -> Result<Vec<Friend>, FriendControllerError> {
+        Ok(sqlx::query_as::<_, (String, Option<String>)>(query)
+            .bind(mayhem_id)
+            .fetch_all(&self.db)
+            .await?
+            .into_iter()
+            .map(|(mayhem_id, name)| Friend { mayhem_id, name })
+            .collect())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    app::models::{
+        dashboard::ConfigEntry,
+        gameplay_config::{EventGameplayToml, GameplayConfigToml},
+    },
+    protos::data::{GameplayConfigResponse, NameValue},
+    util::CachedToml,
+};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+/// a missing file means no overrides
+static OVERRIDES: CachedToml<GameplayConfigToml> =
+    CachedToml::new("gameplay_config.toml", GameplayConfigToml::default);
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameplayConfigControllerError {
+    #[error("Gameplay config items need a name")]
+    InvalidName,
+    #[error("failed to decode json")]
+    JSONDecodeError(#[from] serde_json::Error),
+    #[error("failed to encode gameplay config")]
+    Serialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GameplayConfigController {}
+
+impl GameplayConfigController {
+    /// returns the embedded `GameplayConfig.json` with the items of `gameplay_config.toml` for `event` applied
+    #[instrument(skip(self))]
+    pub fn gameplay_config(
+        &self,
+        event: u64,
+    ) -> Result<GameplayConfigResponse, GameplayConfigControllerError> {
+        let mut gameplay_config = Self::embedded()?;
+        let overrides = OVERRIDES.load()?;
+
+        let items = overrides
+            .items
+            .iter()
+            .chain(overrides.event_items(event).into_iter().flatten());
+
+        for (name, value) in items {
+            match gameplay_config
+                .item
+                .iter_mut()
+                .find(|item| item.name.as_ref() == Some(name))
+            {
+                Some(item) => item.value = Some(value.clone()),
+                None => gameplay_config.item.push(NameValue {
+                    name: Some(name.clone()),
+                    value: Some(value.clone()),
+                }),
+            }
+        }
+
+        Ok(gameplay_config)
+    }
+
+    /// lists every item with its value before and after the overrides of `event`, or the global ones if `None`
+    #[instrument(skip(self))]
+    pub fn entries(
+        &self,
+        event: Option<u64>,
+    ) -> Result<Vec<ConfigEntry>, GameplayConfigControllerError> {
+        let overrides = OVERRIDES.load()?;
+        let mut defaults = Self::embedded()?
+            .item
+            .into_iter()
+            .filter_map(|item| item.name.map(|name| (name, item.value)))
+            .collect::<Vec<_>>();
+
+        let mut items = match event {
+            Some(event) => {
+                // event items are based on the global overrides
+                for (name, value) in &overrides.items {
+                    match defaults.iter_mut().find(|(n, _)| n == name) {
+                        Some((_, default)) => *default = Some(value.clone()),
+                        None => defaults.push((name.clone(), Some(value.clone()))),
+                    }
+                }
+
+                overrides.event_items(event).cloned().unwrap_or_default()
+            }
+            None => overrides.items,
+        };
+
+        let mut entries = defaults
+            .into_iter()
+            .map(|(name, default)| ConfigEntry {
+                value: items.remove(&name),
+                name,
+                default,
+            })
+            .collect::<Vec<_>>();
+
+        entries.extend(items.into_iter().map(|(name, value)| ConfigEntry {
+            name,
+            default: None,
+            value: Some(value),
+        }));
+
+        Ok(entries)
+    }
+
+    /// overrides the item `name` for `event`, or globally if `None`. a `value` of `None` removes the override
+    #[instrument(skip(self))]
+    pub fn set_item(
+        &self,
+        event: Option<u64>,
+        name: String,
+        value: Option<String>,
+    ) -> Result<(), GameplayConfigControllerError> {
+        let name = name.trim().to_owned();
+        if name.is_empty() {
+            return Err(GameplayConfigControllerError::InvalidName);
+        }
+
+        let mut overrides = OVERRIDES.load()?;
+        let items = match event {
+            Some(event) => {
+                if overrides.event_items(event).is_none() {
+                    overrides.events.push(EventGameplayToml {
+                        event,
+                        items: BTreeMap::new(),
+                    });
+                }
+
+                &mut overrides
+                    .events
+                    .iter_mut()
+                    .find(|overlay| overlay.event == event)
+                    .expect("overlay of the event exists")
+                    .items
+            }
+            None => &mut overrides.items,
+        };
+        match value {
+            Some(value) => items.insert(name, value),
+            None => items.remove(&name),
+        };
+        overrides.events.retain(|overlay| !overlay.items.is_empty());
+
+        OVERRIDES.save(&overrides)
+    }
+
+    fn embedded() -> Result<GameplayConfigResponse, GameplayConfigControllerError> {
+        Ok(serde_json::from_str(crate::assets::GAMEPLAY_CONFIG)?)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{database::Database, protos::data::EventMessage, util::millis_from_unix_epoch};
+use prost::Message;
+use tracing::{debug, instrument, warn};
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LandEventControllerError {
+    #[error("No user could be found with that MayhemId")]
+    NotFound,
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+/// queue of events visitors triggered on lands of other players
+#[derive(Debug, Clone)]
+pub struct LandEventController {
+    db: Database,
+}
+
+impl Default for LandEventController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+impl LandEventController {
+    /// stores `events` sent by `from_mayhem_id` for the land of `mayhem_id`.
+    ///
+    /// returns the stored events with ids, sender, receiver and timestamps filled in.
+    /// `now` is the lobby time of the receiver in milliseconds, which is only sent to the client.
+    /// the stored rows are stamped with the wall clock
+    #[instrument(skip(self, events))]
+    pub async fn push(
+        &self,
+        mayhem_id: &str,
+        from_mayhem_id: &str,
+        events: &[EventMessage],
+        now: i64,
+    ) -> Result<Vec<EventMessage>, LandEventControllerError> {
+        const QUERY_USER: &str = "SELECT COUNT(*) FROM users WHERE mayhem_id = ?";
+        // unique events replace the ones of the same sender which were not received yet
+        const DELETE_QUERY: &str = r#"
+            DELETE FROM land_events
+            WHERE mayhem_id = ? AND from_mayhem_id = ? AND delivered_at IS NULL
+                AND event_type IS ? AND target_entity_id IS ?"#;
+        // retried pushes must not queue the same event twice
+        const INSERT_QUERY: &str = r#"
+            INSERT INTO land_events (event_id, mayhem_id, from_mayhem_id, event_type, target_entity_id, event, created_at)
+            SELECT ?, ?, ?, ?, ?, ?, ?
+            WHERE NOT EXISTS (SELECT 1 FROM land_events WHERE mayhem_id = ? AND event_id = ?)"#;
+
+        if sqlx::query_scalar::<_, i64>(QUERY_USER)
+            .bind(mayhem_id)
+            .fetch_one(&self.db)
+            .await?
+            == 0
+        {
+            return Err(LandEventControllerError::NotFound);
+        }
+
+        let created_at = millis_from_unix_epoch()? as i64;
+        let mut tx = self.db.begin().await?;
+        let mut stored = Vec::with_capacity(events.len());
+
+        for event in events {
+            let mut event = event.clone();
+            let event_id = event
+                .id
+                .get_or_insert_with(|| Uuid::new_v4().to_string())
+                .clone();
+            event.to_player_id = Some(mayhem_id.to_owned());
+            event.from_player_id = Some(from_mayhem_id.to_owned());
+            event.created_at.get_or_insert(now);
+            event.updated_at = Some(now);
+
+            if event.unique_event.unwrap_or_default() {
+                sqlx::query(DELETE_QUERY)
+                    .bind(mayhem_id)
+                    .bind(from_mayhem_id)
+                    .bind(&event.event_type)
+                    .bind(&event.target_entity_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            sqlx::query(INSERT_QUERY)
+                .bind(&event_id)
+                .bind(mayhem_id)
+                .bind(from_mayhem_id)
+                .bind(&event.event_type)
+                .bind(&event.target_entity_id)
+                .bind(event.encode_to_vec())
+                .bind(created_at)
+                .bind(mayhem_id)
+                .bind(&event_id)
+                .execute(&mut *tx)
+                .await?;
+
+            stored.push(event);
+        }
+
+        tx.commit().await?;
+
+        debug!(
+            "stored {} events of {from_mayhem_id} for {mayhem_id}",
+            stored.len()
+        );
+
+        Ok(stored)
+    }
+
+    /// returns all events for the land of `mayhem_id` which were not acknowledged yet.
+    ///
+    /// events are sent again with every poll until `acknowledge`, the client tells them apart by their id
+    #[instrument(skip(self))]
+    pub async fn take(
+        &self,
+        mayhem_id: &str,
+    ) -> Result<Vec<EventMessage>, LandEventControllerError> {
+        const QUERY: &str = r#"
+            SELECT id, event FROM land_events
+            WHERE mayhem_id = ? AND delivered_at IS NULL
+            ORDER BY id ASC"#;
+        const UPDATE_QUERY: &str = r#"
+            UPDATE land_events
+            SET sent_at = ?
+            WHERE mayhem_id = ? AND delivered_at IS NULL AND sent_at IS NULL AND id <= ?"#;
+
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query_as::<_, (i64, Vec<u8>)>(QUERY)
+            .bind(mayhem_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let Some((last_id, _)) = rows.last() else {
+            return Ok(vec![]);
+        };
+
+        sqlx::query(UPDATE_QUERY)
+            .bind(millis_from_unix_epoch()? as i64)
+            .bind(mayhem_id)
+            .bind(*last_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let events = rows
+            .into_iter()
+            .filter_map(|(id, event)| match EventMessage::decode(event.as_slice()) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!("failed to decode land event {id} of {mayhem_id}: {e}");
+
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        debug!("delivering {} events to {mayhem_id}", events.len());
+
+        Ok(events)
+    }
+
+    /// marks the events sent to `mayhem_id` before now as received.
+    ///
+    /// called when the client updates its land, which it only does after applying the events
+    #[instrument(skip(self))]
+    pub async fn acknowledge(&self, mayhem_id: &str) -> Result<(), LandEventControllerError> {
+        const QUERY: &str = r#"
+            UPDATE land_events
+            SET delivered_at = ?
+            WHERE mayhem_id = ? AND delivered_at IS NULL AND sent_at < ?"#;
+
+        let now = millis_from_unix_epoch()? as i64;
+        let acknowledged = sqlx::query(QUERY)
+            .bind(now)
+            .bind(mayhem_id)
+            .bind(now)
+            .execute(&self.db)
+            .await?
+            .rows_affected();
+
+        if acknowledged > 0 {
+            debug!("{mayhem_id} received {acknowledged} events");
+        }
+
+        Ok(())
+    }
+}
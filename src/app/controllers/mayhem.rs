@@ -1,18 +1,42 @@
-use crate::protos::MessageFromPath;
-use crate::protos::{CurrencyError, LandError};
-use crate::util::{relative_path, Xml};
+use super::backup::{BackupController, BackupControllerError};
+use super::client_config::{ClientConfigController, ClientConfigControllerError};
+use super::currency::{CurrencyController, CurrencyControllerError};
+use super::event_override::{EventOverrideController, EventOverrideControllerError};
+use super::friend::{FriendController, FriendControllerError};
+use super::gameplay_config::{GameplayConfigController, GameplayConfigControllerError};
+use super::land_event::{LandEventController, LandEventControllerError};
+use super::offers::{OffersController, OffersControllerError};
+use super::purchase::{PurchaseController, PurchaseControllerError};
+use crate::app::models::{
+    currency::{CurrencySource, CurrencyTransaction},
+    purchase::SignedPurchase,
+};
+use crate::protos::{
+    CurrencyError, LandError, RawLand, RawLandBackupResponse, RawMatchmakingResponse,
+};
+use crate::storage::{lock_save, SaveStorage, StorageError, STORAGE};
+use crate::util::Xml;
+use crate::xml_response;
 use crate::{
     config::OPTIONS,
     database::Database,
     protos::data::{
-        ClientConfigResponse, CurrencyData, DeleteTokenResponse, ExtraLandMessage,
-        ExtraLandResponse, LandMessage, TokenData, UserIndirectData, UsersResponseMessage,
-        WholeLandTokenResponse,
+        error_message::{Code, Severity, Type},
+        get_friend_data_response::{
+            friend_data_pair::{AuthService, FriendDataError},
+            FriendDataPair,
+        },
+        order_data::OrderState,
+        ClientConfigResponse, CurrencyData, DeleteTokenResponse, ErrorMessage, EventsMessage,
+        ExtraLandMessage, ExtraLandResponse, GambleResponse, GameplayConfigResponse,
+        GetFriendDataRequest, GetFriendDataResponse, LandMessage, MatchmakingResponseMessage,
+        OffersResponseMessage, OrderData, PurchaseErrorData, PurchaseRequestMessage,
+        PurchaseResponseMessage, PurchasedItemData, TokenData, UserIndirectData,
+        UsersResponseMessage, WholeLandTokenResponse,
     },
     util::millis_from_unix_epoch,
 };
-use crate::{util::DIRECTORIES, xml_response};
-use std::fs::create_dir_all;
+use std::sync::Arc;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
@@ -32,10 +56,32 @@ pub enum MayhemControllerError {
     ProtoCurrencyError(#[from] CurrencyError),
     #[error(transparent)]
     ProtoLandError(#[from] LandError),
+    #[error(transparent)]
+    BackupController(#[from] BackupControllerError),
+    #[error(transparent)]
+    ClientConfigController(#[from] ClientConfigControllerError),
+    #[error(transparent)]
+    CurrencyController(#[from] CurrencyControllerError),
+    #[error(transparent)]
+    EventOverrideController(#[from] EventOverrideControllerError),
+    #[error(transparent)]
+    FriendController(#[from] FriendControllerError),
+    #[error(transparent)]
+    GameplayConfigController(#[from] GameplayConfigControllerError),
+    #[error(transparent)]
+    LandEventController(#[from] LandEventControllerError),
+    #[error(transparent)]
+    OffersController(#[from] OffersControllerError),
+    #[error(transparent)]
+    PurchaseController(#[from] PurchaseControllerError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
     #[error("failed to execute query")]
     DatabaseError(#[from] sqlx::Error),
     #[error("credentials or id not found")]
     NotFound,
+    #[error("events can only be sent to the lands of friends")]
+    NotFriends,
     #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
@@ -44,9 +90,33 @@ pub enum MayhemControllerError {
     Unknown(#[from] anyhow::Error),
 }
 
+impl MayhemControllerError {
+    /// mistakes of the client, which are answered with an error message instead of an internal error
+    fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidAccessToken
+                | Self::InvalidWholeLandToken
+                | Self::ResourceNotExists
+                | Self::ResourceAlreadyExists
+                | Self::NotFound
+                | Self::NotFriends
+                | Self::BackupController(BackupControllerError::NotFound)
+                | Self::FriendController(
+                    FriendControllerError::NotFound | FriendControllerError::SelfFriendship
+                )
+                | Self::LandEventController(LandEventControllerError::NotFound)
+        )
+    }
+}
+
 impl From<MayhemControllerError> for Xml {
     fn from(value: MayhemControllerError) -> Self {
-        tracing::error!("{value}");
+        if value.is_client_error() {
+            warn!("{value}");
+        } else {
+            tracing::error!("{value}");
+        }
 
         match value {
             MayhemControllerError::InvalidAccessToken => {
@@ -64,9 +134,34 @@ impl From<MayhemControllerError> for Xml {
             MayhemControllerError::NotFound => {
                 xml_response!(404, "Invalid AccessToken for specified MayhemId")
             }
+            MayhemControllerError::NotFriends => {
+                xml_response!(403, "Events can only be sent to friends")
+            }
+            MayhemControllerError::BackupController(BackupControllerError::NotFound) => {
+                xml_response!(404, "No LandBackup exists for specified date")
+            }
+            MayhemControllerError::FriendController(FriendControllerError::NotFound) => {
+                xml_response!(404, "No user exists for specified MayhemId")
+            }
+            MayhemControllerError::FriendController(FriendControllerError::SelfFriendship) => {
+                xml_response!("Users can not befriend themselves")
+            }
+            MayhemControllerError::LandEventController(LandEventControllerError::NotFound) => {
+                xml_response!(404, "No user exists for specified MayhemId")
+            }
             MayhemControllerError::JSONDecodeError(_)
             | MayhemControllerError::ProtoCurrencyError(_)
             | MayhemControllerError::ProtoLandError(_)
+            | MayhemControllerError::BackupController(_)
+            | MayhemControllerError::ClientConfigController(_)
+            | MayhemControllerError::CurrencyController(_)
+            | MayhemControllerError::EventOverrideController(_)
+            | MayhemControllerError::FriendController(_)
+            | MayhemControllerError::GameplayConfigController(_)
+            | MayhemControllerError::LandEventController(_)
+            | MayhemControllerError::OffersController(_)
+            | MayhemControllerError::PurchaseController(_)
+            | MayhemControllerError::Storage(_)
             | MayhemControllerError::DatabaseError(_)
             | MayhemControllerError::IO(_)
             | MayhemControllerError::Time(_)
@@ -78,6 +173,14 @@ impl From<MayhemControllerError> for Xml {
 #[derive(Debug, Clone)]
 pub struct MayhemController {
     db: Database,
+    backups: BackupController,
+    currency: CurrencyController,
+    event_overrides: EventOverrideController,
+    friends: FriendController,
+    land_events: LandEventController,
+    offers: OffersController,
+    purchases: PurchaseController,
+    storage: Arc<dyn SaveStorage>,
 }
 
 impl Default for MayhemController {
@@ -87,6 +190,14 @@ impl Default for MayhemController {
                 .get()
                 .expect("database is initialized")
                 .clone(),
+            backups: BackupController::default(),
+            currency: CurrencyController::default(),
+            event_overrides: EventOverrideController::default(),
+            friends: FriendController::default(),
+            land_events: LandEventController::default(),
+            offers: OffersController::default(),
+            purchases: PurchaseController::default(),
+            storage: STORAGE.get().expect("storage is initialized").clone(),
         }
     }
 }
@@ -94,19 +205,7 @@ impl Default for MayhemController {
 impl MayhemController {
     #[instrument]
     pub async fn client_config() -> Result<ClientConfigResponse, MayhemControllerError> {
-        let client_config_response: ClientConfigResponse =
-            serde_json::from_str(crate::assets::CLIENT_CONFIG)?;
-
-        // client_config_response.items.iter_mut().for_each(|c| {
-        //     match c.name.as_ref().map_or("", |c| c.as_str()) {
-        //         "ServerSaveInterval" => {
-        //             c.value = Some("0.01".to_owned());
-        //         }
-        //         _ => {}
-        //     }
-        // });
-
-        Ok(client_config_response)
+        Ok(ClientConfigController::default().client_config()?)
     }
 
     #[instrument(skip(self))]
@@ -283,7 +382,7 @@ impl MayhemController {
         mayhem_id: &String,
         header_token: &String,
         land_update_token: &String,
-    ) -> Result<LandMessage, MayhemControllerError> {
+    ) -> Result<RawLand, MayhemControllerError> {
         let db = &self.db;
 
         const QUERY: &str = r#"
@@ -311,32 +410,23 @@ impl MayhemController {
                     return Err(MayhemControllerError::InvalidWholeLandToken);
                 }
 
-                let mut path = if OPTIONS.take().portable {
-                    relative_path().map_err(anyhow::Error::from)?
-                } else {
-                    DIRECTORIES.data_local_dir().to_path_buf()
-                };
-                path.push(format!("{mayhem_id}/land.pb"));
-
-                if !path.exists() {
-                    warn!("path does not exist: {path:?}");
+                let _guard = lock_save(mayhem_id).await;
 
-                    create_dir_all(path.parent().expect("data dir exists"))?;
+                let Some(land) = self.storage.load_land(mayhem_id).await? else {
+                    info!("creating {mayhem_id} land save");
 
-                    info!("creating {mayhem_id} save {path:?}");
-
-                    let land = LandMessage::new(mayhem_id)?;
-                    land.save(path)?;
+                    let land = RawLand::from(LandMessage::new(mayhem_id)?);
+                    self.storage.save_land(mayhem_id, &land.bytes).await?;
 
                     return Ok(land);
-                }
+                };
 
-                let mut land = LandMessage::load(&path)?;
+                let mut land = RawLand::decode(land)?;
 
-                if land.id != Some(mayhem_id.to_owned()) {
+                if land.land.id != Some(mayhem_id.to_owned()) {
                     warn!("saved id mismatch. overwriting: {mayhem_id}");
 
-                    land.id = Some(mayhem_id.to_owned());
+                    land.set_id(mayhem_id);
                 }
 
                 Ok(land)
@@ -354,14 +444,14 @@ impl MayhemController {
         }
     }
 
-    #[instrument]
+    #[instrument(skip(self, land_message))]
     // /mh/bg_gameserver_plugin/protoland/{landId}
     pub async fn update_protoland(
         &self,
         mayhem_id: &String,
         header_token: &String,
         land_update_token: &String,
-        land_message: &LandMessage,
+        land_message: &RawLand,
         force: bool,
     ) -> Result<(), MayhemControllerError> {
         let db = &self.db;
@@ -370,6 +460,10 @@ impl MayhemController {
             SELECT user_access_token, whole_land_token
             FROM users
             WHERE mayhem_id = ?"#;
+        const LEVEL_QUERY: &str = r#"
+            UPDATE users
+            SET land_level = ?
+            WHERE mayhem_id = ?"#;
 
         match sqlx::query_as::<_, (String, String)>(QUERY)
             .bind(mayhem_id)
@@ -391,22 +485,30 @@ impl MayhemController {
                     return Err(MayhemControllerError::InvalidWholeLandToken);
                 }
 
-                let mut path = if OPTIONS.take().portable {
-                    relative_path().map_err(anyhow::Error::from)?
-                } else {
-                    DIRECTORIES.data_local_dir().to_path_buf()
-                };
-                path.push(format!("{mayhem_id}/land.pb"));
+                let _guard = lock_save(mayhem_id).await;
 
-                if !path.exists() {
-                    if !force {
-                        return Err(MayhemControllerError::ResourceNotExists);
-                    }
-
-                    create_dir_all(path.parent().expect("data dir exists"))?;
+                if !force && !self.storage.land_exists(mayhem_id).await? {
+                    return Err(MayhemControllerError::ResourceNotExists);
                 }
 
-                land_message.save(&path)?;
+                self.storage
+                    .save_land(mayhem_id, &land_message.bytes)
+                    .await?;
+                self.backups.record(mayhem_id, &land_message.bytes).await?;
+                self.land_events.acknowledge(mayhem_id).await?;
+
+                if let Some(level) = land_message
+                    .land
+                    .friend_data
+                    .as_ref()
+                    .and_then(|friend_data| friend_data.level)
+                {
+                    sqlx::query(LEVEL_QUERY)
+                        .bind(level)
+                        .bind(mayhem_id)
+                        .execute(db)
+                        .await?;
+                }
 
                 Ok(())
             }
@@ -423,6 +525,96 @@ impl MayhemController {
         }
     }
 
+    #[instrument(skip(self))]
+    // /mh/bg_gameserver_plugin/protoland/{landId}/backups/
+    pub async fn land_backups(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+        page: u32,
+    ) -> Result<RawLandBackupResponse, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token
+            FROM users
+            WHERE mayhem_id = ?"#;
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok(access_token) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                Ok(self.backups.backups(mayhem_id, page).await?)
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn list_land_backups(
+        &self,
+        mayhem_id: &String,
+    ) -> Result<Vec<u64>, MayhemControllerError> {
+        Ok(self.backups.list(mayhem_id).await?)
+    }
+
+    /// overwrites the current land of `mayhem_id` with the revision stored at `date`.
+    ///
+    /// the whole land token gets revoked so a running client can not overwrite the restored land with its local state
+    #[instrument(skip(self))]
+    pub async fn restore_land_backup(
+        &self,
+        mayhem_id: &String,
+        date: u64,
+    ) -> Result<(), MayhemControllerError> {
+        let db = &self.db;
+
+        const UPDATE_QUERY: &str = r#"
+            UPDATE users
+            SET whole_land_token = ?
+            WHERE mayhem_id = ?"#;
+
+        let mut land = self.backups.get(mayhem_id, date).await?;
+        land.set_id(mayhem_id);
+
+        let _guard = lock_save(mayhem_id).await;
+        self.storage.save_land(mayhem_id, &land.bytes).await?;
+
+        if sqlx::query(UPDATE_QUERY)
+            .bind("")
+            .bind(mayhem_id)
+            .execute(db)
+            .await?
+            .rows_affected()
+            == 0
+        {
+            warn!("restored land of unknown user {mayhem_id}");
+        }
+
+        info!("restored land revision {date} of {mayhem_id}");
+
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     // /mh/games/bg_gameserver_plugin/protocurrency/{mayhem_id}
     pub async fn proto_currency(
@@ -451,41 +643,10 @@ impl MayhemController {
                     return Err(MayhemControllerError::InvalidAccessToken);
                 }
 
-                let mut path = if OPTIONS.take().portable {
-                    relative_path().map_err(anyhow::Error::from)?
-                } else {
-                    DIRECTORIES.data_local_dir().to_path_buf()
-                };
-                path.push(format!("{mayhem_id}/currency.pb"));
-
-                let currency = if !path.exists() {
-                    info!("creating {mayhem_id} save {path:?}");
-
-                    create_dir_all(path.parent().expect("data dir exists"))
-                        .map_err(|e| MayhemControllerError::Unknown(e.into()))?;
-
-                    let epoch = millis_from_unix_epoch()
-                        .map_err(|e| MayhemControllerError::Unknown(e.into()))?
-                        as i64;
-
-                    let config = OPTIONS.take();
-                    let data = CurrencyData {
-                        id: Some(mayhem_id.clone()),
-                        vc_total_purchased: Some(0),
-                        vc_total_awarded: Some(config.default_donuts as i32),
-                        vc_balance: Some(config.default_donuts as i32),
-                        created_at: Some(epoch),
-                        updated_at: Some(epoch),
-                        unverified: None,
-                    };
-
-                    data.save(&path)?;
-
-                    data
-                } else {
-                    debug!("currency exists: {mayhem_id}");
+                let currency = {
+                    let _guard = lock_save(mayhem_id).await;
 
-                    CurrencyData::load(&path)?
+                    self.load_currency(mayhem_id).await?
                 };
 
                 debug!("loaded currency: {currency:?}");
@@ -539,69 +700,74 @@ impl MayhemController {
                     return Err(MayhemControllerError::InvalidWholeLandToken);
                 }
 
-                let mut path = if OPTIONS.take().portable {
-                    relative_path().map_err(anyhow::Error::from)?
-                } else {
-                    DIRECTORIES.data_local_dir().to_path_buf()
-                };
-                path.push(format!("{mayhem_id}/currency.pb"));
-
-                let currency = if !path.exists() {
-                    info!("creating {mayhem_id} save {path:?}");
-
-                    create_dir_all(path.parent().expect("data dir exists"))
-                        .map_err(|e| MayhemControllerError::Unknown(e.into()))?;
-
-                    let epoch = millis_from_unix_epoch()
-                        .map_err(|e| MayhemControllerError::Unknown(e.into()))?
-                        as i64;
-                    let config = OPTIONS.take();
-
-                    let data = CurrencyData {
-                        id: Some(mayhem_id.clone()),
-                        vc_total_purchased: Some(0),
-                        vc_total_awarded: Some(config.default_donuts as i32),
-                        vc_balance: Some(config.default_donuts as i32),
-                        created_at: Some(epoch),
-                        updated_at: Some(epoch),
-                        unverified: None,
-                    };
+                // events of earlier responses were applied by the client before this update
+                self.land_events.acknowledge(mayhem_id).await?;
 
-                    data.save(&path)?;
+                // hold the save lock until the updated currency is written,
+                // so retried or concurrent updates can not apply their deltas onto a stale balance
+                let _guard = lock_save(mayhem_id).await;
+                let mut currency = self.load_currency(mayhem_id).await?;
 
-                    data
-                } else {
-                    debug!("currency exists: {mayhem_id}");
-                    CurrencyData::load(&path)?
-                };
+                let epoch = self.player_time(mayhem_id).await?;
+                let created_at = millis_from_unix_epoch()? as i64;
+                let request_id = Uuid::new_v4().to_string();
 
-                let mut donut_delta = 0;
                 let mut processed_currency_delta = vec![];
+                let mut transactions = vec![];
                 for delta in extra_land_message.currency_delta.iter() {
-                    donut_delta += delta.amount.unwrap_or(0);
+                    // rejected deltas are left out of the response, so the client does not consider them applied
+                    let Some(transaction) = Self::apply_delta(
+                        mayhem_id,
+                        &mut currency,
+                        CurrencySource::Client,
+                        delta.reason.clone(),
+                        delta.amount.unwrap_or(0),
+                        &request_id,
+                        created_at,
+                    ) else {
+                        continue;
+                    };
 
+                    transactions.push(transaction);
                     processed_currency_delta.push(delta.clone());
                 }
 
-                let new_total = currency.vc_total_awarded.unwrap_or_default() + donut_delta;
+                self.save_currency(mayhem_id, &mut currency, &transactions, epoch)
+                    .await?;
+
+                let mut processed_event = vec![];
+                for event in extra_land_message.event.iter() {
+                    let Some(to_player_id) = event.to_player_id.as_ref() else {
+                        warn!("event of {mayhem_id} has no receiver: {:?}", event.id);
 
-                let epoch = millis_from_unix_epoch()
-                    .map_err(|e| MayhemControllerError::Unknown(e.into()))?
-                    as i64;
+                        continue;
+                    };
+
+                    if !self.may_send_events(mayhem_id, to_player_id).await? {
+                        warn!("event of {mayhem_id} for {to_player_id}, who is not a friend");
+
+                        continue;
+                    }
 
-                CurrencyData {
-                    id: currency.id,
-                    vc_total_purchased: currency.vc_total_purchased,
-                    vc_total_awarded: Some(new_total),
-                    vc_balance: Some(new_total),
-                    created_at: currency.created_at,
-                    updated_at: Some(epoch),
-                    unverified: None,
+                    let now = self.player_time(to_player_id).await?;
+
+                    match self
+                        .land_events
+                        .push(to_player_id, mayhem_id, std::slice::from_ref(event), now)
+                        .await
+                    {
+                        Ok(events) => processed_event.extend(events),
+                        Err(LandEventControllerError::NotFound) => {
+                            warn!("event of {mayhem_id} for unknown user {to_player_id}");
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
                 }
-                .save(&path)?;
 
                 Ok(ExtraLandResponse {
                     processed_currency_delta,
+                    processed_event,
+                    received_event: self.land_events.take(mayhem_id).await?,
                     ..Default::default()
                 })
             }
@@ -618,6 +784,603 @@ impl MayhemController {
         }
     }
 
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/protopurchase/{mayhem_id}/
+    pub async fn purchase(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+        request: &PurchaseRequestMessage,
+    ) -> Result<PurchaseResponseMessage, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token
+            FROM users
+            WHERE mayhem_id = ?"#;
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok(access_token) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                let signed = SignedPurchase::parse(request, OPTIONS.take().donut_packs.keys());
+                let payment = request.payment_info.first();
+                let purchase_token = signed
+                    .purchase_token
+                    .or_else(|| request.purchase_token.clone());
+                let order_id = signed
+                    .order_id
+                    .or_else(|| purchase_token.clone())
+                    .or_else(|| payment.and_then(|payment| payment.transaction_id.clone()))
+                    .or_else(|| SignedPurchase::receipt_id(request));
+                let order = |state: OrderState| OrderData {
+                    notification_id: None,
+                    order_id: order_id.clone(),
+                    state: Some(state as i32),
+                    platform: request.platform,
+                    purchase_token: purchase_token.clone(),
+                    signed_data: request.signed_data.clone(),
+                };
+
+                let _guard = lock_save(mayhem_id).await;
+                let mut currency = self.load_currency(mayhem_id).await?;
+
+                // without an id, retries of the purchase could not be told apart
+                let Some(order_id) = order_id.clone() else {
+                    warn!("purchase of {mayhem_id} has neither an order id nor a receipt");
+
+                    return Ok(Self::purchase_error(
+                        currency,
+                        order(OrderState::Failed),
+                        400,
+                        "INVALID_RECEIPT",
+                    ));
+                };
+
+                let Some(product_id) = signed.product_id else {
+                    warn!("purchase of {mayhem_id} does not contain a product id: {order_id}");
+
+                    return Ok(Self::purchase_error(
+                        currency,
+                        order(OrderState::Failed),
+                        400,
+                        "INVALID_RECEIPT",
+                    ));
+                };
+
+                // claimed first, so a failure after granting the donuts can not grant them twice
+                if !self
+                    .purchases
+                    .claim(mayhem_id, &order_id, &product_id, request.platform)
+                    .await?
+                {
+                    info!("order {order_id} of {mayhem_id} was already fulfilled");
+
+                    return Ok(PurchaseResponseMessage {
+                        currency: Some(currency),
+                        order_data: vec![order(OrderState::Ignored)],
+                        ..Default::default()
+                    });
+                }
+
+                let donuts = OPTIONS.take().donut_packs.get(&product_id).copied();
+                let Some(donuts) = donuts.and_then(|donuts| i32::try_from(donuts).ok()) else {
+                    warn!("no donut pack configured for {product_id}");
+
+                    self.purchases
+                        .finish(&order_id, 0, OrderState::Failed)
+                        .await?;
+
+                    return Ok(Self::purchase_error(
+                        currency,
+                        order(OrderState::Failed),
+                        404,
+                        "UNKNOWN_PRODUCT",
+                    ));
+                };
+
+                let balance = currency.vc_balance.unwrap_or_default() as i64;
+                if !currency.apply_purchase(donuts) {
+                    warn!("purchase of {product_id} exceeds the balance of {mayhem_id}");
+
+                    self.purchases
+                        .finish(&order_id, 0, OrderState::Failed)
+                        .await?;
+
+                    return Ok(Self::purchase_error(
+                        currency,
+                        order(OrderState::Failed),
+                        400,
+                        "BALANCE_OVERFLOW",
+                    ));
+                }
+
+                let epoch = self.player_time(mayhem_id).await?;
+                let transaction = CurrencyTransaction::new(
+                    mayhem_id,
+                    CurrencySource::Purchase,
+                    Some(product_id.clone()),
+                    donuts as i64,
+                    balance,
+                    &order_id,
+                    millis_from_unix_epoch()? as i64,
+                );
+
+                // the order stays pending if this fails, it may have been saved already
+                self.save_currency(mayhem_id, &mut currency, &[transaction], epoch)
+                    .await?;
+                self.purchases
+                    .finish(&order_id, donuts as i64, OrderState::Valid)
+                    .await?;
+
+                info!("granted {donuts} donuts to {mayhem_id} for {product_id}");
+
+                Ok(PurchaseResponseMessage {
+                    currency: Some(currency),
+                    purchases: vec![PurchasedItemData {
+                        product_id: Some(product_id),
+                        purchase_price: payment.and_then(|payment| payment.purchase_price.clone()),
+                        purchase_date: Some(epoch),
+                    }],
+                    error: None,
+                    order_data: vec![order(OrderState::Valid)],
+                    subscriptions: vec![],
+                })
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/protooffers/{mayhem_id}/
+    pub async fn offers(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+    ) -> Result<OffersResponseMessage, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token
+            FROM users
+            WHERE mayhem_id = ?"#;
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok(access_token) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                let event = self.event_overrides.event(mayhem_id).await?;
+                let now = self.player_time(mayhem_id).await? / 1000;
+
+                Ok(self.offers.offers(event, now as u64)?)
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/friendData
+    pub async fn friend_data(
+        &self,
+        header_token: &String,
+        request: &GetFriendDataRequest,
+    ) -> Result<GetFriendDataResponse, MayhemControllerError> {
+        let mayhem_id = self.user_by_token(header_token).await?;
+        let friends = self.friends.friends(&mayhem_id).await?;
+        let mut friend_data = vec![];
+
+        for friend_id in request.friend_id.iter() {
+            let land = if friends.iter().any(|friend| friend.mayhem_id == *friend_id) {
+                self.storage.load_land(friend_id).await?
+            } else {
+                debug!("{friend_id} is no friend of {mayhem_id}");
+
+                None
+            };
+            let data = land.and_then(|land| match RawLand::decode(land) {
+                Ok(land) => land.land.friend_data,
+                Err(e) => {
+                    warn!("failed to decode land of {friend_id}: {e}");
+
+                    None
+                }
+            });
+
+            friend_data.push(FriendDataPair {
+                friend_id: Some(friend_id.clone()),
+                error: data.is_none().then(|| FriendDataError {
+                    code: Some(404),
+                    r#type: Some("NOT_FOUND".to_owned()),
+                }),
+                friend_data: data,
+                auth_service: Some(AuthService::Nucleus as i32),
+                external_id: None,
+            });
+        }
+
+        Ok(GetFriendDataResponse {
+            friend_data,
+            error: None,
+        })
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/event/{mayhem_id}/protoland/
+    pub async fn events(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+    ) -> Result<EventsMessage, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token
+            FROM users
+            WHERE mayhem_id = ?"#;
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok(access_token) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                Ok(EventsMessage {
+                    event: self.land_events.take(mayhem_id).await?,
+                })
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    /// stores the `events` the client sends along with an event poll.
+    ///
+    /// the owner of `mayhem_id` gets its queued events back, friends sending events get the stored ones
+    #[instrument(skip(self, events))]
+    // /mh/games/bg_gameserver_plugin/event/{mayhem_id}/protoland/
+    pub async fn poll_events(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+        events: &EventsMessage,
+    ) -> Result<EventsMessage, MayhemControllerError> {
+        if events.event.is_empty() {
+            return self.events(mayhem_id, header_token).await;
+        }
+
+        let pushed = self.push_events(mayhem_id, header_token, events).await?;
+
+        // the queue of a friend's land belongs to the friend
+        if self.user_by_token(header_token).await? != *mayhem_id {
+            return Ok(pushed);
+        }
+
+        self.events(mayhem_id, header_token).await
+    }
+
+    #[instrument(skip(self, events))]
+    // /mh/games/bg_gameserver_plugin/event/{mayhem_id}/protoland/
+    pub async fn push_events(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+        events: &EventsMessage,
+    ) -> Result<EventsMessage, MayhemControllerError> {
+        let from_mayhem_id = self.user_by_token(header_token).await?;
+
+        if !self.may_send_events(&from_mayhem_id, mayhem_id).await? {
+            warn!("{from_mayhem_id} is not a friend of {mayhem_id}");
+
+            return Err(MayhemControllerError::NotFriends);
+        }
+
+        let now = self.player_time(mayhem_id).await?;
+
+        Ok(EventsMessage {
+            event: self
+                .land_events
+                .push(mayhem_id, &from_mayhem_id, &events.event, now)
+                .await?,
+        })
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/matchmaking/{mayhem_id}/
+    pub async fn matchmaking(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+    ) -> Result<RawMatchmakingResponse, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token, land_level
+            FROM users
+            WHERE mayhem_id = ?"#;
+        // lands within the level range come first, ties are broken randomly.
+        // lands saved before levels were recorded have none until their next upload and come last
+        const MATCH_QUERY: &str = r#"
+            SELECT mayhem_id
+            FROM users
+            WHERE mayhem_id != ? AND matchmaking_opt_out = 0
+            ORDER BY
+                CASE
+                    WHEN land_level IS NULL THEN 2
+                    WHEN ? IS NULL OR ABS(land_level - ?) <= ? THEN 0
+                    ELSE 1
+                END,
+                RANDOM()
+            LIMIT ?"#;
+        // a few candidates are tried, in case a land can not be loaded
+        const CANDIDATES: i64 = 5;
+
+        match sqlx::query_as::<_, (String, Option<i32>)>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok((access_token, level)) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                let range = OPTIONS.take().matchmaking_level_range as i64;
+                // without a range every land is considered similar
+                let level = level.filter(|_| range > 0);
+
+                let candidates = sqlx::query_scalar::<_, u64>(MATCH_QUERY)
+                    .bind(mayhem_id)
+                    .bind(level)
+                    .bind(level)
+                    .bind(range)
+                    .bind(CANDIDATES)
+                    .fetch_all(db)
+                    .await?;
+
+                for candidate in candidates.iter().map(u64::to_string) {
+                    match self.storage.load_land(&candidate).await {
+                        Ok(Some(land)) => {
+                            let land = match RawLand::decode(land) {
+                                Ok(land) => land,
+                                Err(e) => {
+                                    warn!("failed to decode land of {candidate}: {e}");
+
+                                    continue;
+                                }
+                            };
+                            debug!("matched {mayhem_id} with {candidate}");
+
+                            return Ok(RawMatchmakingResponse::matched(land));
+                        }
+                        Ok(None) => debug!("{candidate} has no land save"),
+                        Err(e) => warn!("failed to load land of {candidate}: {e}"),
+                    }
+                }
+
+                info!("no land to match with {mayhem_id}");
+
+                Ok(MatchmakingResponseMessage {
+                    matched_user_land: None,
+                    error: Some(Self::error_message(
+                        Code::NotFound,
+                        Type::NoSuchResource,
+                        "No land available",
+                    )),
+                }
+                .into())
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/bg_gameserver_plugin/gamble/{mayhem_id}/
+    pub async fn gamble(
+        &self,
+        mayhem_id: &String,
+        header_token: &String,
+    ) -> Result<GambleResponse, MayhemControllerError> {
+        let db = &self.db;
+
+        const QUERY: &str = r#"
+            SELECT user_access_token
+            FROM users
+            WHERE mayhem_id = ?"#;
+
+        match sqlx::query_scalar::<_, String>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(db)
+            .await
+        {
+            Ok(access_token) => {
+                debug!("user found: {mayhem_id}");
+
+                if access_token != *header_token {
+                    warn!("token mismatch: {header_token} != {access_token}");
+
+                    return Err(MayhemControllerError::InvalidAccessToken);
+                }
+
+                let gamble = OPTIONS.take().gamble.clone();
+                let cost = i32::try_from(gamble.cost).unwrap_or(i32::MAX);
+
+                let Some(prize) = gamble.draw() else {
+                    error!("gamble prize table is empty");
+
+                    return Ok(GambleResponse {
+                        updated_currency: None,
+                        currency_awarded: None,
+                        error: Some(Self::error_message(
+                            Code::ServiceUnavailable,
+                            Type::ServiceUnavailableType,
+                            "No prizes configured",
+                        )),
+                    });
+                };
+                let prize = i32::try_from(prize).unwrap_or(i32::MAX);
+
+                let _guard = lock_save(mayhem_id).await;
+                let mut currency = self.load_currency(mayhem_id).await?;
+
+                let epoch = self.player_time(mayhem_id).await?;
+                let created_at = millis_from_unix_epoch()? as i64;
+                let request_id = Uuid::new_v4().to_string();
+
+                let Some(cost_transaction) = Self::apply_delta(
+                    mayhem_id,
+                    &mut currency,
+                    CurrencySource::Gamble,
+                    Some("cost".to_owned()),
+                    -cost,
+                    &request_id,
+                    created_at,
+                ) else {
+                    return Ok(GambleResponse {
+                        updated_currency: Some(currency),
+                        currency_awarded: Some(0),
+                        error: Some(Self::error_message(
+                            Code::BadRequest,
+                            Type::ValueTooSmall,
+                            "Not enough donuts",
+                        )),
+                    });
+                };
+
+                let mut transactions = vec![cost_transaction];
+                if prize > 0 {
+                    transactions.extend(Self::apply_delta(
+                        mayhem_id,
+                        &mut currency,
+                        CurrencySource::Gamble,
+                        Some("prize".to_owned()),
+                        prize,
+                        &request_id,
+                        created_at,
+                    ));
+                }
+                let awarded = transactions.get(1).map_or(0, |prize| prize.amount as i32);
+
+                self.save_currency(mayhem_id, &mut currency, &transactions, epoch)
+                    .await?;
+
+                info!(
+                    "{mayhem_id} gambled {} donuts and won {awarded}",
+                    gamble.cost
+                );
+
+                Ok(GambleResponse {
+                    updated_currency: Some(currency),
+                    currency_awarded: Some(awarded),
+                    error: None,
+                })
+            }
+            Err(sqlx::Error::RowNotFound) => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::NotFound)
+            }
+            Err(e) => {
+                error!("{e}");
+
+                Err(MayhemControllerError::DatabaseError(e))
+            }
+        }
+    }
+
+    fn error_message(code: Code, kind: Type, message: &str) -> ErrorMessage {
+        ErrorMessage {
+            code: Some(code as i32),
+            r#type: Some(kind as i32),
+            field: None,
+            severity: Some(Severity::LevelWarn as i32),
+            message: Some(message.to_owned()),
+        }
+    }
+
+    fn purchase_error(
+        currency: CurrencyData,
+        order: OrderData,
+        code: i32,
+        kind: &str,
+    ) -> PurchaseResponseMessage {
+        PurchaseResponseMessage {
+            currency: Some(currency),
+            error: Some(PurchaseErrorData {
+                code: Some(code),
+                r#type: Some(kind.to_owned()),
+            }),
+            order_data: vec![order],
+            ..Default::default()
+        }
+    }
+
     #[instrument(skip(self))]
     // /mh/users
     pub async fn set_user(
@@ -706,8 +1469,179 @@ impl MayhemController {
         }
     }
 
-    // TODO change to setting
+    /// loads the currency of `mayhem_id` or creates it with the configured default donuts.
+    /// the ledger gets reconciled with the loaded balance
+    ///
+    /// callers have to hold the save lock of `mayhem_id`
+    async fn load_currency(
+        &self,
+        mayhem_id: &String,
+    ) -> Result<CurrencyData, MayhemControllerError> {
+        if let Some(currency) = self.storage.load_currency(mayhem_id).await? {
+            debug!("currency exists: {mayhem_id}");
+
+            self.currency.reconcile(mayhem_id, &currency).await?;
+
+            return Ok(currency);
+        }
+
+        info!("creating {mayhem_id} currency save");
+
+        let epoch = self.player_time(mayhem_id).await?;
+        let default_donuts = OPTIONS.take().default_donuts as i32;
+
+        let currency = CurrencyData {
+            id: Some(mayhem_id.clone()),
+            vc_total_purchased: Some(0),
+            vc_total_awarded: Some(default_donuts),
+            vc_balance: Some(default_donuts),
+            created_at: Some(epoch),
+            updated_at: Some(epoch),
+            unverified: None,
+        };
+
+        self.storage.save_currency(mayhem_id, &currency).await?;
+        self.currency.reconcile(mayhem_id, &currency).await?;
+
+        Ok(currency)
+    }
+
+    /// resolves the mayhem id of the user owning `header_token`
+    async fn user_by_token(&self, header_token: &String) -> Result<String, MayhemControllerError> {
+        const QUERY: &str = r#"SELECT mayhem_id FROM users WHERE user_access_token = ?"#;
+
+        match sqlx::query_scalar::<_, u64>(QUERY)
+            .bind(header_token)
+            .fetch_optional(&self.db)
+            .await?
+        {
+            Some(mayhem_id) => Ok(mayhem_id.to_string()),
+            None => {
+                warn!("user not found");
+
+                Err(MayhemControllerError::InvalidAccessToken)
+            }
+        }
+    }
+
+    /// applies `amount` onto `currency` as reported by the client.
+    ///
+    /// returns the ledger entry or `None` if the delta would take the balance below zero
+    fn apply_delta(
+        mayhem_id: &str,
+        currency: &mut CurrencyData,
+        source: CurrencySource,
+        reason: Option<String>,
+        amount: i32,
+        request_id: &str,
+        created_at: i64,
+    ) -> Option<CurrencyTransaction> {
+        let balance = currency.vc_balance.unwrap_or_default() as i64;
+
+        if !currency.apply_delta(amount) {
+            warn!("rejecting currency delta of {mayhem_id}: {balance} {amount:+} ({source}, {reason:?})");
+
+            return None;
+        }
+
+        Some(CurrencyTransaction::new(
+            mayhem_id,
+            source,
+            reason,
+            amount as i64,
+            balance,
+            request_id,
+            created_at,
+        ))
+    }
+
+    /// persists `currency` together with the ledger entries which led to it
+    ///
+    /// callers have to hold the save lock of `mayhem_id`
+    async fn save_currency(
+        &self,
+        mayhem_id: &String,
+        currency: &mut CurrencyData,
+        transactions: &[CurrencyTransaction],
+        epoch: i64,
+    ) -> Result<(), MayhemControllerError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        currency.updated_at = Some(epoch);
+        currency.unverified = None;
+
+        // the ledger is written first, a balance must never change without its ledger rows
+        self.currency.record(transactions).await?;
+        self.storage.save_currency(mayhem_id, currency).await?;
+
+        debug!(
+            "currency of {mayhem_id}: {} purchased, {} awarded, {} spent, {} balance",
+            currency.vc_total_purchased.unwrap_or_default(),
+            currency.vc_total_awarded.unwrap_or_default(),
+            currency.vc_total_spent(),
+            currency.vc_balance.unwrap_or_default()
+        );
+
+        Ok(())
+    }
+
+    /// current time of the lobby clock, which follows the selected event
     pub fn get_lobby_time() -> Result<u128, MayhemControllerError> {
-        Ok(crate::util::millis_from_unix_epoch()?)
+        Ok(OPTIONS.take().lobby_time()?)
+    }
+
+    #[instrument(skip(self))]
+    // /mh/games/lobby/time
+    pub async fn lobby_time(
+        &self,
+        header_token: Option<&String>,
+    ) -> Result<u128, MayhemControllerError> {
+        // the time is requested before login as well, which gets the global clock
+        let Some(header_token) = header_token else {
+            return Self::get_lobby_time();
+        };
+
+        match self.user_by_token(header_token).await {
+            Ok(mayhem_id) => Ok(self.event_overrides.lobby_time(&mayhem_id).await?),
+            Err(MayhemControllerError::InvalidAccessToken) => Self::get_lobby_time(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// gameplay config of the event the player of `header_token` is in, the global event without a token
+    #[instrument(skip(self, header_token))]
+    pub async fn gameplay_config(
+        &self,
+        header_token: Option<&String>,
+    ) -> Result<GameplayConfigResponse, MayhemControllerError> {
+        let event = match header_token {
+            Some(header_token) => match self.user_by_token(header_token).await {
+                Ok(mayhem_id) => self.event_overrides.event(&mayhem_id).await?,
+                Err(MayhemControllerError::InvalidAccessToken) => OPTIONS.take().current_event,
+                Err(e) => return Err(e),
+            },
+            None => OPTIONS.take().current_event,
+        };
+
+        Ok(GameplayConfigController::default().gameplay_config(event)?)
+    }
+
+    /// players may send events to their own land and to the lands of their friends
+    async fn may_send_events(
+        &self,
+        from_mayhem_id: &str,
+        mayhem_id: &str,
+    ) -> Result<bool, MayhemControllerError> {
+        Ok(from_mayhem_id == mayhem_id
+            || self.friends.are_friends(from_mayhem_id, mayhem_id).await?)
+    }
+
+    /// lobby time of `mayhem_id` in milliseconds, see `EventOverrideController`.
+    ///
+    /// only used for what the client sees, stored rows are stamped with the wall clock
+    async fn player_time(&self, mayhem_id: &str) -> Result<i64, MayhemControllerError> {
+        Ok(self.event_overrides.lobby_time(mayhem_id).await? as i64)
     }
 }
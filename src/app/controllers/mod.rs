@@ -1,7 +1,17 @@
 pub mod auth;
+pub mod backup;
+pub mod client_config;
+pub mod client_version;
+pub mod currency;
 pub mod dashboard;
 pub mod direction;
+pub mod event_override;
 pub mod events;
+pub mod friend;
+pub mod gameplay_config;
+pub mod land_event;
 pub mod mayhem;
+pub mod offers;
 pub mod proxy;
+pub mod purchase;
 pub mod user;
@@ -0,0 +1,51 @@
+use crate::{
+    app::models::offers::OffersToml, protos::data::OffersResponseMessage, util::CachedToml,
+};
+use tracing::instrument;
+
+/// a missing file means no offers
+static OFFERS: CachedToml<OffersToml> = CachedToml::new("offers.toml", OffersToml::default);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OffersControllerError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OffersController {}
+
+impl OffersController {
+    /// returns all offers of `offers.toml` which are active for `event` at `now` in seconds
+    #[instrument(skip(self))]
+    pub fn offers(
+        &self,
+        event: u64,
+        now: u64,
+    ) -> Result<OffersResponseMessage, OffersControllerError> {
+        let offers = OFFERS.load()?;
+
+        Ok(OffersResponseMessage {
+            offer_items: offers
+                .offers
+                .iter()
+                .filter(|offer| offer.window.is_active(event, now))
+                .map(Into::into)
+                .collect(),
+            popularity_range_list: vec![],
+            error: None,
+            level_up_xp_offers: offers
+                .level_up_offers
+                .iter()
+                .filter(|offer| offer.window.is_active(event, now))
+                .map(Into::into)
+                .collect(),
+            price_override_offer: offers
+                .price_overrides
+                .iter()
+                .filter(|offer| offer.window.is_active(event, now))
+                .map(Into::into)
+                .collect(),
+        })
+    }
+}
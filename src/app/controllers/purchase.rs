@@ -0,0 +1,102 @@
+use crate::{
+    database::Database, protos::data::order_data::OrderState, util::millis_from_unix_epoch,
+};
+use tracing::{debug, instrument};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PurchaseControllerError {
+    #[error("failed to execute query")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchaseController {
+    db: Database,
+}
+
+impl Default for PurchaseController {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+/// state of claimed orders whose donuts are being granted, the client never sees it
+const PENDING: i32 = -1;
+
+impl PurchaseController {
+    /// claims `order_id` for `mayhem_id`, so no other request grants donuts for it.
+    ///
+    /// returns `false` if the order is already claimed or fulfilled, failed orders can be claimed again
+    #[instrument(skip(self))]
+    pub async fn claim(
+        &self,
+        mayhem_id: &str,
+        order_id: &str,
+        product_id: &str,
+        platform: Option<i32>,
+    ) -> Result<bool, PurchaseControllerError> {
+        const QUERY: &str = r#"
+            INSERT INTO purchases (mayhem_id, order_id, product_id, platform, donuts, state, created_at)
+            VALUES (?, ?, ?, ?, 0, ?, ?)
+            ON CONFLICT(order_id) DO UPDATE SET
+                mayhem_id = excluded.mayhem_id,
+                product_id = excluded.product_id,
+                platform = excluded.platform,
+                donuts = excluded.donuts,
+                state = excluded.state,
+                created_at = excluded.created_at
+            WHERE purchases.state = ?"#;
+
+        let claimed = sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(order_id)
+            .bind(product_id)
+            .bind(platform)
+            .bind(PENDING)
+            .bind(millis_from_unix_epoch()? as i64)
+            .bind(OrderState::Failed as i32)
+            .execute(&self.db)
+            .await?
+            .rows_affected()
+            > 0;
+
+        debug!("claimed order {order_id} of {mayhem_id}: {claimed}");
+
+        Ok(claimed)
+    }
+
+    /// stores the outcome of the claimed `order_id`.
+    ///
+    /// orders which stay pending because this fails are never granted again
+    #[instrument(skip(self))]
+    pub async fn finish(
+        &self,
+        order_id: &str,
+        donuts: i64,
+        state: OrderState,
+    ) -> Result<(), PurchaseControllerError> {
+        const QUERY: &str = r#"
+            UPDATE purchases
+            SET donuts = ?, state = ?
+            WHERE order_id = ? AND state = ?"#;
+
+        sqlx::query(QUERY)
+            .bind(donuts)
+            .bind(state as i32)
+            .bind(order_id)
+            .bind(PENDING)
+            .execute(&self.db)
+            .await?;
+
+        debug!("finished order {order_id}: {state:?}");
+
+        Ok(())
+    }
+}
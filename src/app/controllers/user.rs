@@ -1,7 +1,11 @@
 use crate::{
-    app::models::user::{
-        AuthResponse, AuthenticatorLoginType, DeviceIdResponse, EALoginRequest, HeaderCheckOptions,
-        LoginResponse, Token, TokenInfoResponse, TokenResponse, UidResponse,
+    app::models::{
+        dashboard::Player,
+        user::{
+            AuthResponse, AuthenticatorLoginType, DeviceIdResponse, EALoginRequest,
+            HeaderCheckOptions, LoginResponse, Token, TokenInfoResponse, TokenResponse,
+            UidResponse,
+        },
     },
     config::OPTIONS,
     database::Database,
@@ -329,4 +333,50 @@ impl UserController {
             .fetch_one(&self.db)
             .await?)
     }
+
+    #[instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<Player>, UserControllerError> {
+        const QUERY: &str = r#"
+            SELECT mayhem_id, user_id, user_name, user_email, matchmaking_opt_out
+            FROM users
+            ORDER BY user_id ASC"#;
+
+        Ok(
+            sqlx::query_as::<_, (u64, u64, Option<String>, Option<String>, bool)>(QUERY)
+                .fetch_all(&self.db)
+                .await?
+                .into_iter()
+                .map(
+                    |(mayhem_id, user_id, name, email, matchmaking_opt_out)| Player {
+                        mayhem_id: mayhem_id.to_string(),
+                        user_id: user_id.to_string(),
+                        name,
+                        email,
+                        matchmaking_opt_out,
+                    },
+                )
+                .collect(),
+        )
+    }
+
+    /// excludes the land of `mayhem_id` from matchmaking or includes it again
+    #[instrument(skip(self))]
+    pub async fn set_matchmaking_opt_out(
+        &self,
+        mayhem_id: &str,
+        opt_out: bool,
+    ) -> Result<bool, UserControllerError> {
+        const QUERY: &str = r#"
+            UPDATE users
+            SET matchmaking_opt_out = ?
+            WHERE mayhem_id = ?"#;
+
+        Ok(sqlx::query(QUERY)
+            .bind(opt_out)
+            .bind(mayhem_id)
+            .execute(&self.db)
+            .await?
+            .rows_affected()
+            > 0)
+    }
 }
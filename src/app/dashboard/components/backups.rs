@@ -0,0 +1,32 @@
+use super::super::server::*;
+use crate::load_gate;
+use chrono::{TimeZone, Utc};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn BackupSection(mayhem_id: ReadOnlySignal<String>) -> Element {
+    let mut backups = use_resource(move || async move { get_land_backups(mayhem_id()).await });
+    let mut restored: Signal<Option<u64>> = use_signal(|| None);
+
+    rsx! {
+        div { class: "p-6 overflow-scroll",
+            h2 { class: "text-lg font-semibold", {t!("backups_header")} " {mayhem_id}" }
+            if let Some(date) = restored() {
+                p { class: "mt-3 text-success", {t!("backups_restored")} " {date}" }
+            }
+            {
+                load_gate!(
+                    backups(), dates => { rsx! { if dates.is_empty() { p { class : "mt-3", {
+                    t!("backups_empty") } } } else { ul { class : "mt-3", for date in dates { li {
+                    class : "flex justify-between items-center mt-1", span { { Utc
+                    .timestamp_millis_opt(date as i64).single().map(| time | time.to_string())
+                    .unwrap_or_else(|| date.to_string()) } } button { class :
+                    "btn btn-warning btn-sm", onclick : move | _ | async move { if
+                    restore_land_backup(mayhem_id(), date). await .is_ok() { restored.set(Some(date));
+                    } backups.restart(); }, { t!("backups_restore") } } } } } } } }
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::super::server::*;
+use crate::{app::models::dashboard::ConfigEntry, load_gate};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+pub type OnSetCallback = Callback<(String, Option<String>)>;
+
+#[component]
+pub fn ClientConfigSection() -> Element {
+    let mut entries = use_resource(get_client_config);
+    let on_set: OnSetCallback = use_callback(move |(name, value)| {
+        spawn(async move {
+            set_client_config_item(name, value).await.ok();
+            entries.restart();
+        });
+    });
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("client_config_header")} }
+            {
+                load_gate!(
+                    entries(), entries => { rsx! { ConfigEditor { entries, on_set } } }
+                )
+            }
+        }
+    }
+}
+
+/// table of config items with a form to add items, `on_set` gets `None` to remove an override
+#[component]
+pub fn ConfigEditor(entries: Vec<ConfigEntry>, on_set: OnSetCallback) -> Element {
+    let mut name = use_signal(String::new);
+    let mut value = use_signal(String::new);
+
+    rsx! {
+        table { class: "table table-sm mt-1",
+            tbody {
+                for entry in entries {
+                    ConfigRow { key: "{entry.name}{entry.value:?}", entry, on_set }
+                }
+            }
+        }
+        div { class: "flex gap-2 mt-2",
+            input {
+                class: "input input-bordered input-sm grow",
+                placeholder: t!("config_item_name"),
+                value: name(),
+                oninput: move |event| name.set(event.value()),
+            }
+            input {
+                class: "input input-bordered input-sm grow",
+                placeholder: t!("config_item_value"),
+                value: value(),
+                oninput: move |event| value.set(event.value()),
+            }
+            button {
+                class: "btn btn-primary btn-sm",
+                disabled: name().trim().is_empty(),
+                onclick: move |_| {
+                    on_set.call((name(), Some(value())));
+                    name.set(String::new());
+                    value.set(String::new());
+                },
+                {t!("config_item_add")}
+            }
+        }
+    }
+}
+
+#[component]
+fn ConfigRow(entry: ConfigEntry, on_set: OnSetCallback) -> Element {
+    let mut value = use_signal(|| {
+        entry
+            .value
+            .clone()
+            .or(entry.default.clone())
+            .unwrap_or_default()
+    });
+    let save_name = entry.name.clone();
+    let reset_name = entry.name.clone();
+
+    rsx! {
+        tr {
+            td { class: "font-mono", "{entry.name}" }
+            td {
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    class: if entry.value.is_some() { "input-primary" },
+                    value: value(),
+                    oninput: move |event| value.set(event.value()),
+                }
+            }
+            td { class: "flex gap-2",
+                button {
+                    class: "btn btn-primary btn-sm",
+                    onclick: move |_| on_set.call((save_name.clone(), Some(value()))),
+                    {t!("config_item_save")}
+                }
+                if entry.value.is_some() {
+                    button {
+                        class: "btn btn-warning btn-sm",
+                        onclick: move |_| on_set.call((reset_name.clone(), None)),
+                        // items only the override file has are removed
+                        if entry.default.is_some() {
+                            {t!("config_item_reset")}
+                        } else {
+                            {t!("config_item_remove")}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::super::server::*;
+use crate::{
+    app::models::dashboard::{
+        ClientVersionPolicy, ClientVersionsResponse, SeenClientVersion, VersionRules, VersionStatus,
+    },
+    load_gate,
+};
+use chrono::{TimeZone, Utc};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn ClientVersionSection() -> Element {
+    let mut data = use_resource(get_client_versions);
+    let on_change = use_callback(move |_: ()| data.restart());
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("client_versions_header")} }
+            {
+                load_gate!(
+                    data(), data => { rsx! { ClientVersionEditor { data, on_change } } }
+                )
+            }
+        }
+    }
+}
+
+#[component]
+fn ClientVersionEditor(data: ClientVersionsResponse, on_change: Callback<()>) -> Element {
+    let mut policy: Signal<ClientVersionPolicy> = use_signal(|| data.policy.clone());
+    let mut platform = use_signal(String::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let platforms = policy().platforms;
+
+    rsx! {
+        table { class: "table table-sm mt-1",
+            thead {
+                tr {
+                    th { {t!("client_versions_platform")} }
+                    th { {t!("client_versions_minimum")} }
+                    th { {t!("client_versions_current")} }
+                    th { {t!("client_versions_allowed")} }
+                    th {}
+                }
+            }
+            tbody {
+                VersionRulesRow {
+                    name: t!("client_versions_default"),
+                    rules: policy().default,
+                    on_change: move |rules| policy.write().default = rules,
+                }
+                for (name , rules) in platforms {
+                    VersionRulesRow {
+                        key: "{name}",
+                        name: name.clone(),
+                        rules,
+                        on_change: {
+                            let name = name.clone();
+
+                            move |rules| {
+                                policy.write().platforms.insert(name.clone(), rules);
+                            }
+                        },
+                        on_remove: move |_| {
+                            policy.write().platforms.remove(&name);
+                        },
+                    }
+                }
+            }
+        }
+        div { class: "flex gap-2 mt-2",
+            input {
+                class: "input input-bordered input-sm grow",
+                placeholder: t!("client_versions_platform"),
+                value: platform(),
+                oninput: move |event| platform.set(event.value()),
+            }
+            button {
+                class: "btn btn-sm",
+                disabled: platform().trim().is_empty(),
+                onclick: move |_| {
+                    let name = platform().trim().to_ascii_lowercase();
+                    policy.write().platforms.entry(name).or_default();
+                    platform.set(String::new());
+                },
+                {t!("client_versions_add")}
+            }
+            button {
+                class: "btn btn-primary btn-sm",
+                onclick: move |_| async move {
+                    match set_client_version_policy(policy()).await {
+                        Ok(()) => error.set(None),
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                    on_change.call(());
+                },
+                {t!("client_versions_save")}
+            }
+        }
+        if let Some(error) = error() {
+            p { class: "mt-1 text-error", "{error}" }
+        }
+        SeenVersions { seen: data.seen }
+    }
+}
+
+#[component]
+fn VersionRulesRow(
+    name: String,
+    rules: VersionRules,
+    on_change: Callback<VersionRules>,
+    on_remove: Option<Callback<()>>,
+) -> Element {
+    let version = |value: String| Some(value.trim().to_owned()).filter(|value| !value.is_empty());
+    let minimum = rules.clone();
+    let current = rules.clone();
+    let allowed = rules.clone();
+
+    rsx! {
+        tr {
+            td { "{name}" }
+            td {
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    value: rules.minimum.clone().unwrap_or_default(),
+                    oninput: move |event| {
+                        on_change.call(VersionRules {
+                            minimum: version(event.value()),
+                            ..minimum.clone()
+                        })
+                    },
+                }
+            }
+            td {
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    value: rules.current.clone().unwrap_or_default(),
+                    oninput: move |event| {
+                        on_change.call(VersionRules {
+                            current: version(event.value()),
+                            ..current.clone()
+                        })
+                    },
+                }
+            }
+            td {
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    value: rules.allowed.join(", "),
+                    oninput: move |event| {
+                        let versions = event
+                            .value()
+                            .split(',')
+                            .filter_map(|value| version(value.to_owned()))
+                            .collect();
+
+                        on_change.call(VersionRules {
+                            allowed: versions,
+                            ..allowed.clone()
+                        })
+                    },
+                }
+            }
+            td {
+                if let Some(on_remove) = on_remove {
+                    button {
+                        class: "btn btn-error btn-sm",
+                        onclick: move |_| on_remove.call(()),
+                        {t!("client_versions_remove")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SeenVersions(seen: Vec<SeenClientVersion>) -> Element {
+    if seen.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        h3 { class: "font-semibold mt-3", {t!("client_versions_seen")} }
+        table { class: "table table-sm mt-1",
+            tbody {
+                for version in seen {
+                    tr {
+                        td { {version.platform.clone().unwrap_or_default()} }
+                        td { class: "font-mono", "{version.version}" }
+                        td {
+                            {
+                                Utc.timestamp_opt(version.last_seen as i64, 0)
+                                    .single()
+                                    .map(|time| time.to_string())
+                                    .unwrap_or_else(|| version.last_seen.to_string())
+                            }
+                        }
+                        td {
+                            match version.status {
+                                VersionStatus::Allowed => rsx! {
+                                    span { class: "text-success",
+                                        {t!("client_versions_allowed_status")}
+                                    }
+                                },
+                                VersionStatus::UpgradeAvailable => rsx! {
+                                    span { class: "text-warning",
+                                        {t!("client_versions_upgrade_status")}
+                                    }
+                                },
+                                VersionStatus::Rejected => rsx! {
+                                    span { class: "text-error",
+                                        {t!("client_versions_rejected_status")}
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
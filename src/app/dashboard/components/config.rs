@@ -2,12 +2,18 @@ use crate::{
     app::{
         dashboard::{
             components::{
-                address::AddressSection, dlc::DlcSection, donuts::DefaultDonuts,
-                events::EventDropdownSection,
+                address::AddressSection,
+                client_config::ClientConfigSection,
+                client_versions::ClientVersionSection,
+                dlc::DlcSection,
+                donuts::DefaultDonuts,
+                events::{EventCatalogSection, EventDropdownSection, EventScheduleSection},
+                gameplay_config::GameplayConfigSection,
+                lobby::LobbyClockSection,
             },
             server::*,
         },
-        models::dashboard::ServerConfigResponse,
+        models::dashboard::{ConfigReloadResponse, ServerConfigResponse},
     },
     load_gate,
 };
@@ -68,11 +74,14 @@ pub fn ConfigSection() -> Element {
                     .parse()
                     .expect("provided callback value should already be validated")
             }
-            OnChangeKey::CurrentEvent => current_event.set(Some(
-                value
+            OnChangeKey::CurrentEvent => {
+                let ts = value
                     .parse()
-                    .expect("provided callback value should already be validated"),
-            )),
+                    .expect("provided callback value should already be validated");
+
+                new_config.current_event = ts;
+                current_event.set(Some(ts));
+            }
         };
 
         submit_disabled.set(!validate_config(&new_config));
@@ -121,6 +130,65 @@ pub fn ConfigSection() -> Element {
                     }
                 }
             }
+            ReloadSection {}
+            LobbyClockSection {}
+            EventScheduleSection {}
+            EventCatalogSection {}
+            ClientConfigSection {}
+            GameplayConfigSection {}
+            ClientVersionSection {}
+        }
+    }
+}
+
+#[component]
+fn ReloadSection() -> Element {
+    let mut result: Signal<Option<Result<ConfigReloadResponse, ServerFnError>>> =
+        use_signal(|| None);
+
+    rsx! {
+        div { class: "mt-3",
+            button {
+                class: "btn btn-secondary",
+                onclick: move |_| async move {
+                    result.set(Some(reload_config().await));
+                },
+                {t!("config_reload")}
+            }
+            match result() {
+                Some(Ok(reload)) => rsx! {
+                    ReloadResult { reload }
+                },
+                Some(Err(e)) => rsx! {
+                    p { class: "mt-1 text-error", "{e}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+#[component]
+fn ReloadResult(reload: ConfigReloadResponse) -> Element {
+    rsx! {
+        if reload.applied.is_empty() && reload.restart_required.is_empty() {
+            p { class: "mt-1", {t!("config_reload_unchanged")} }
+        }
+        if !reload.applied.is_empty() {
+            p { class: "mt-1 text-success", {t!("config_reload_applied")} }
+            ul {
+                for setting in reload.applied {
+                    li { class: "font-mono", "{setting}" }
+                }
+            }
+        }
+        if !reload.restart_required.is_empty() {
+            p { class: "mt-1 text-warning", {t!("config_reload_restart")} }
+            ul {
+                for setting in reload.restart_required {
+                    li { class: "font-mono", "{setting}" }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,51 @@
+use super::super::server::*;
+use crate::{app::models::currency::CURRENCY_TRANSACTIONS_PER_PAGE, load_gate};
+use chrono::{TimeZone, Utc};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn CurrencySection(mayhem_id: ReadOnlySignal<String>) -> Element {
+    let mut page = use_signal(|| 0u32);
+    let transactions =
+        use_resource(move || async move { get_currency_transactions(mayhem_id(), page()).await });
+    // a full page means that there may be older transactions
+    let has_older = use_memo(move || match &*transactions.read() {
+        Some(Ok(transactions)) => transactions.len() == CURRENCY_TRANSACTIONS_PER_PAGE as usize,
+        _ => false,
+    });
+
+    rsx! {
+        div { class: "p-6 overflow-scroll",
+            h2 { class: "text-lg font-semibold", {t!("currency_header")} " {mayhem_id}" }
+            {
+                load_gate!(
+                    transactions(), transactions => { rsx! { if transactions.is_empty() { p {
+                    class : "mt-3", { t!("currency_empty") } } } else { table { class :
+                    "table mt-3", thead { tr { th { { t!("currency_date") } } th { {
+                    t!("currency_source") } } th { { t!("currency_amount") } } th { {
+                    t!("currency_balance") } } } } tbody { for transaction in transactions { tr
+                    { td { { Utc.timestamp_millis_opt(transaction.created_at).single().map(|
+                    time | time.to_string()).unwrap_or_else(|| transaction.created_at
+                    .to_string()) } } td { "{transaction.source}" if let Some(reason) =
+                    &transaction.reason { " ({reason})" } } td { "{transaction.amount:+}" } td {
+                    "{transaction.balance_after}" } } } } } } } }
+                )
+            }
+            div { class: "flex gap-2 mt-3",
+                button {
+                    class: "btn btn-sm",
+                    disabled: page() == 0,
+                    onclick: move |_| page -= 1,
+                    {t!("currency_newer")}
+                }
+                button {
+                    class: "btn btn-sm",
+                    disabled: !has_older(),
+                    onclick: move |_| page += 1,
+                    {t!("currency_older")}
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,13 @@ use super::{
     super::server::*,
     config::{OnChangeCallback, OnChangeKey},
 };
-use crate::{app::models::dashboard::EventsResponse, load_gate};
+use crate::{
+    app::models::dashboard::{
+        CatalogEvent, EventOverride, EventsResponse, PlaylistEntry, ScheduledEvent,
+    },
+    load_gate,
+};
+use chrono::{TimeZone, Utc};
 use dioxus::prelude::*;
 use dioxus_i18n::t;
 
@@ -39,3 +45,398 @@ pub fn EventDropdownSection(data: EventsResponse, on_change: OnChangeCallback) -
         }
     }
 }
+
+#[component]
+pub fn EventScheduleSection() -> Element {
+    let mut schedule = use_resource(get_event_schedule);
+    let mut enabled = use_signal(|| false);
+    let mut interval_days = use_signal(|| 14_u64);
+    let on_change = use_callback(move |_: ()| schedule.restart());
+
+    use_effect(move || {
+        if let Some(Ok(schedule)) = schedule() {
+            enabled.set(schedule.enabled);
+            interval_days.set((schedule.interval / DAY).max(1));
+        }
+    });
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("rotation_header")} }
+            label { class: "label cursor-pointer justify-start gap-3 mt-1",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-primary",
+                    checked: enabled(),
+                    onchange: move |event| enabled.set(event.checked()),
+                }
+                span { {t!("rotation_enabled")} }
+            }
+            label { class: "block mt-1", {t!("rotation_interval")} }
+            div { class: "flex gap-2",
+                input {
+                    r#type: "number",
+                    class: "input input-bordered w-full",
+                    min: 1,
+                    value: interval_days(),
+                    oninput: move |event| {
+                        if let Ok(value) = event.value().parse::<u64>() {
+                            interval_days.set(value.max(1));
+                        }
+                    },
+                }
+                button {
+                    class: "btn btn-primary",
+                    onclick: move |_| async move {
+                        set_event_rotation(enabled(), interval_days() * DAY).await.ok();
+                        schedule.restart();
+                    },
+                    {t!("rotation_save")}
+                }
+                button {
+                    class: "btn btn-warning",
+                    onclick: move |_| async move {
+                        next_event().await.ok();
+                        schedule.restart();
+                    },
+                    {t!("rotation_next")}
+                }
+            }
+            {
+                load_gate!(
+                    schedule(), schedule => { rsx! { UpcomingEvents { upcoming : schedule
+                    .upcoming } PlaylistEditor { playlist : schedule.playlist, on_change } } }
+                )
+            }
+        }
+    }
+}
+
+#[component]
+fn UpcomingEvents(upcoming: Vec<ScheduledEvent>) -> Element {
+    if upcoming.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        h3 { class: "font-semibold mt-3", {t!("rotation_upcoming")} }
+        table { class: "table mt-1",
+            tbody {
+                for event in upcoming {
+                    tr {
+                        td { "{event.name}" }
+                        td {
+                            {
+                                Utc.timestamp_opt(event.starts_at as i64, 0)
+                                    .single()
+                                    .map(|time| time.to_string())
+                                    .unwrap_or_else(|| event.starts_at.to_string())
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PlaylistEditor(playlist: Vec<PlaylistEntry>, on_change: Callback<()>) -> Element {
+    let events = use_resource(get_events);
+    let mut selected: Signal<Option<u64>> = use_signal(|| None);
+    let names = match events() {
+        Some(Ok(events)) => events.events,
+        _ => vec![],
+    };
+    let name = |ts: u64| {
+        names
+            .iter()
+            .find(|(start, _)| *start == ts)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| ts.to_string())
+    };
+    let update = move |playlist: Vec<PlaylistEntry>| async move {
+        set_event_playlist(playlist).await.ok();
+        on_change.call(());
+    };
+
+    rsx! {
+        h3 { class: "font-semibold mt-3", {t!("rotation_playlist")} }
+        ul { class: "mt-1",
+            for (index , entry) in playlist.iter().enumerate() {
+                li { class: "flex justify-between items-center mt-1",
+                    span { {name(entry.event)} }
+                    button {
+                        class: "btn btn-error btn-sm",
+                        onclick: {
+                            let playlist = playlist.clone();
+
+                            move |_| {
+                                let mut playlist = playlist.clone();
+                                playlist.remove(index);
+
+                                update(playlist)
+                            }
+                        },
+                        {t!("rotation_remove")}
+                    }
+                }
+            }
+        }
+        div { class: "flex gap-2 mt-2",
+            select {
+                class: "select select-primary w-full",
+                oninput: move |e| selected.set(e.value().parse().ok()),
+                option { value: "", {t!("rotation_select")} }
+                // normal play is not part of the rotation
+                for (ts , name) in names.iter().filter(|(ts, _)| *ts != 0) {
+                    option { value: ts.to_string(), "{name}" }
+                }
+            }
+            button {
+                class: "btn btn-primary",
+                disabled: selected().is_none(),
+                onclick: {
+                    let playlist = playlist.clone();
+
+                    move |_| {
+                        let mut playlist = playlist.clone();
+                        if let Some(event) = selected() {
+                            playlist.push(PlaylistEntry {
+                                event,
+                                duration: None,
+                            });
+                        }
+
+                        update(playlist)
+                    }
+                },
+                {t!("rotation_add")}
+            }
+        }
+    }
+}
+
+const DAY: u64 = 60 * 60 * 24;
+
+#[component]
+pub fn EventCatalogSection() -> Element {
+    let mut catalog = use_resource(get_event_catalog);
+    let mut current: Signal<CatalogEvent> = use_signal(CatalogEvent::default);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+    let on_edit = use_callback(move |event: CatalogEvent| current.set(event));
+    let on_change = use_callback(move |_: ()| catalog.restart());
+    let duration_days = current()
+        .duration
+        .map(|duration| (duration / DAY).to_string())
+        .unwrap_or_default();
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("catalog_header")} }
+            {
+                load_gate!(
+                    catalog(), catalog => { rsx! { CatalogTable { catalog, on_edit, on_change } }
+                    }
+                )
+            }
+            div { class: "grid grid-cols-2 gap-2 mt-2",
+                input {
+                    r#type: "number",
+                    class: "input input-bordered input-sm",
+                    placeholder: t!("catalog_start"),
+                    min: 0,
+                    value: current().start,
+                    oninput: move |event| {
+                        if let Ok(start) = event.value().parse() {
+                            current.write().start = start;
+                        }
+                    },
+                }
+                input {
+                    class: "input input-bordered input-sm",
+                    placeholder: t!("catalog_name"),
+                    value: current().name,
+                    oninput: move |event| current.write().name = event.value(),
+                }
+                input {
+                    r#type: "number",
+                    class: "input input-bordered input-sm",
+                    placeholder: t!("catalog_duration"),
+                    min: 1,
+                    value: duration_days,
+                    oninput: move |event| {
+                        current.write().duration = event
+                            .value()
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|days| *days > 0)
+                            .map(|days| days * DAY);
+                    },
+                }
+                input {
+                    class: "input input-bordered input-sm",
+                    placeholder: t!("catalog_packages"),
+                    value: current().packages.join(", "),
+                    oninput: move |event| {
+                        current.write().packages = event
+                            .value()
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|package| !package.is_empty())
+                            .map(str::to_owned)
+                            .collect();
+                    },
+                }
+            }
+            button {
+                class: "mt-2 btn btn-primary btn-sm",
+                onclick: move |_| async move {
+                    match save_catalog_event(current()).await {
+                        Ok(()) => {
+                            error.set(None);
+                            current.set(CatalogEvent::default());
+                        }
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                    catalog.restart();
+                },
+                {t!("catalog_save")}
+            }
+            if let Some(error) = error() {
+                p { class: "mt-1 text-error", "{error}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn CatalogTable(
+    catalog: Vec<CatalogEvent>,
+    on_edit: Callback<CatalogEvent>,
+    on_change: Callback<()>,
+) -> Element {
+    rsx! {
+        table { class: "table table-sm mt-1",
+            tbody {
+                for event in catalog {
+                    tr {
+                        td { "{event.start}" }
+                        td { "{event.name}" }
+                        td {
+                            if let Some(duration) = event.duration {
+                                "{duration / DAY} "
+                                {t!("catalog_days")}
+                            }
+                        }
+                        td { {event.packages.join(", ")} }
+                        td { class: "flex gap-2",
+                            button {
+                                class: "btn btn-sm",
+                                onclick: {
+                                    let event = event.clone();
+
+                                    move |_| on_edit.call(event.clone())
+                                },
+                                {t!("catalog_edit")}
+                            }
+                            // normal play is required
+                            if event.start != 0 {
+                                button {
+                                    class: "btn btn-error btn-sm",
+                                    onclick: move |_| async move {
+                                        remove_catalog_event(event.start).await.ok();
+                                        on_change.call(());
+                                    },
+                                    {t!("catalog_remove")}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn EventOverrideSection(mayhem_id: ReadOnlySignal<String>) -> Element {
+    let event_override = use_resource(move || async move { get_event_override(mayhem_id()).await });
+    let events = use_resource(get_events);
+    let mut enabled = use_signal(|| false);
+    let mut current: Signal<EventOverride> = use_signal(EventOverride::default);
+    let mut saved = use_signal(|| false);
+
+    use_effect(move || {
+        if let Some(Ok(event_override)) = event_override() {
+            enabled.set(event_override.is_some());
+            current.set(event_override.unwrap_or_default());
+            saved.set(false);
+        }
+    });
+
+    rsx! {
+        div { class: "p-6 overflow-scroll",
+            h2 { class: "text-lg font-semibold", {t!("override_header")} " {mayhem_id}" }
+            label { class: "label cursor-pointer justify-start gap-3 mt-3",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-primary",
+                    checked: enabled(),
+                    onchange: move |event| enabled.set(event.checked()),
+                }
+                span { {t!("override_enabled")} }
+            }
+            if enabled() {
+                select {
+                    class: "select select-primary w-full mt-1",
+                    oninput: move |e| {
+                        if let Ok(event) = e.value().parse() {
+                            current.write().event = event;
+                        }
+                    },
+                    if let Some(Ok(events)) = events() {
+                        for (ts , name) in events.events {
+                            option {
+                                value: ts.to_string(),
+                                selected: ts == current().event,
+                                "{name}"
+                            }
+                        }
+                    }
+                }
+                label { class: "label cursor-pointer justify-start gap-3 mt-1",
+                    input {
+                        r#type: "checkbox",
+                        class: "toggle toggle-primary",
+                        checked: current().frozen,
+                        onchange: move |event| current.write().frozen = event.checked(),
+                    }
+                    span { {t!("lobby_clock_frozen")} }
+                }
+                label { class: "block mt-1", {t!("lobby_clock_offset")} }
+                input {
+                    r#type: "number",
+                    class: "input input-bordered w-full",
+                    value: current().offset,
+                    oninput: move |event| {
+                        if let Ok(offset) = event.value().parse() {
+                            current.write().offset = offset;
+                        }
+                    },
+                }
+            }
+            button {
+                class: "mt-3 btn btn-primary btn-sm",
+                onclick: move |_| async move {
+                    let event_override = enabled().then_some(current());
+                    saved.set(set_event_override(mayhem_id(), event_override).await.is_ok());
+                },
+                {t!("override_save")}
+            }
+            if saved() {
+                p { class: "mt-3 text-success", {t!("override_saved")} }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::super::server::*;
+use crate::{app::models::friend::Friend, load_gate};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn FriendSection(mayhem_id: ReadOnlySignal<String>) -> Element {
+    let mut friends = use_resource(move || async move { get_friends(mayhem_id()).await });
+    let mut code = use_resource(move || async move { get_friend_code(mayhem_id()).await });
+    let mut redeem_code = use_signal(String::new);
+    let on_change = use_callback(move |_: ()| friends.restart());
+
+    rsx! {
+        div { class: "p-6 overflow-scroll",
+            h2 { class: "text-lg font-semibold", {t!("friends_header")} " {mayhem_id}" }
+            div { class: "flex justify-between items-center mt-3",
+                span {
+                    {t!("friends_code")}
+                    ": "
+                    if let Some(Ok(code)) = code() {
+                        span { class: "font-mono", "{code}" }
+                    }
+                }
+                button {
+                    class: "btn btn-warning btn-sm",
+                    onclick: move |_| async move {
+                        let _ = regenerate_friend_code(mayhem_id()).await;
+                        code.restart();
+                    },
+                    {t!("friends_regenerate")}
+                }
+            }
+            div { class: "flex gap-2 mt-3",
+                input {
+                    class: "input input-bordered input-sm grow",
+                    placeholder: t!("friends_redeem_placeholder"),
+                    value: redeem_code(),
+                    oninput: move |event| redeem_code.set(event.value()),
+                }
+                button {
+                    class: "btn btn-primary btn-sm",
+                    onclick: move |_| async move {
+                        if redeem_friend_code(mayhem_id(), redeem_code()).await.is_ok() {
+                            redeem_code.set(String::new());
+                        }
+                        friends.restart();
+                    },
+                    {t!("friends_redeem")}
+                }
+            }
+            {
+                load_gate!(
+                    friends(), friends => { rsx! { if friends.friends.is_empty() { p { class :
+                    "mt-3", { t!("friends_empty") } } } else { ul { class : "mt-3", for friend in
+                    friends.friends { FriendRow { mayhem_id, friend, on_change } } } } if !friends
+                    .requests.is_empty() { h3 { class : "font-semibold mt-3", {
+                    t!("friends_requests") } } ul { class : "mt-1", for requester in friends
+                    .requests { RequestRow { mayhem_id, requester, on_change } } } } } }
+                )
+            }
+        }
+    }
+}
+
+#[component]
+fn FriendRow(
+    mayhem_id: ReadOnlySignal<String>,
+    friend: Friend,
+    on_change: Callback<()>,
+) -> Element {
+    let name = friend.name.unwrap_or_default();
+    let friend_id = friend.mayhem_id.clone();
+
+    rsx! {
+        li { class: "flex justify-between items-center mt-1",
+            span { "{friend.mayhem_id} {name}" }
+            button {
+                class: "btn btn-error btn-sm",
+                onclick: move |_| {
+                    let friend_id = friend_id.clone();
+
+                    async move {
+                        let _ = remove_friend(mayhem_id(), friend_id).await;
+                        on_change.call(());
+                    }
+                },
+                {t!("friends_remove")}
+            }
+        }
+    }
+}
+
+#[component]
+fn RequestRow(
+    mayhem_id: ReadOnlySignal<String>,
+    requester: Friend,
+    on_change: Callback<()>,
+) -> Element {
+    let name = requester.name.unwrap_or_default();
+    let accept_id = requester.mayhem_id.clone();
+    let decline_id = requester.mayhem_id.clone();
+
+    rsx! {
+        li { class: "flex justify-between items-center mt-1",
+            span { "{requester.mayhem_id} {name}" }
+            div { class: "flex gap-2",
+                button {
+                    class: "btn btn-primary btn-sm",
+                    onclick: move |_| {
+                        let requester = accept_id.clone();
+
+                        async move {
+                            let _ = accept_friend_request(mayhem_id(), requester).await;
+                            on_change.call(());
+                        }
+                    },
+                    {t!("friends_accept")}
+                }
+                button {
+                    class: "btn btn-error btn-sm",
+                    onclick: move |_| {
+                        let requester = decline_id.clone();
+
+                        async move {
+                            let _ = remove_friend(mayhem_id(), requester).await;
+                            on_change.call(());
+                        }
+                    },
+                    {t!("friends_decline")}
+                }
+            }
+        }
+    }
+}
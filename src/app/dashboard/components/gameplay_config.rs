@@ -0,0 +1,41 @@
+use super::{
+    super::server::*,
+    client_config::{ConfigEditor, OnSetCallback},
+};
+use crate::load_gate;
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn GameplayConfigSection() -> Element {
+    let events = use_resource(get_events);
+    let mut event: Signal<Option<u64>> = use_signal(|| None);
+    let mut entries = use_resource(move || async move { get_gameplay_config(event()).await });
+    let on_set: OnSetCallback = use_callback(move |(name, value)| {
+        spawn(async move {
+            set_gameplay_config_item(event(), name, value).await.ok();
+            entries.restart();
+        });
+    });
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("gameplay_config_header")} }
+            select {
+                class: "select select-primary w-full",
+                oninput: move |e| event.set(e.value().parse().ok()),
+                option { value: "", {t!("gameplay_config_global")} }
+                if let Some(Ok(events)) = events() {
+                    for (ts , name) in events.events {
+                        option { value: ts.to_string(), "{name}" }
+                    }
+                }
+            }
+            {
+                load_gate!(
+                    entries(), entries => { rsx! { ConfigEditor { entries, on_set } } }
+                )
+            }
+        }
+    }
+}
@@ -21,3 +21,62 @@ pub fn LobbySection() -> Element {
         }
     }
 }
+
+#[component]
+pub fn LobbyClockSection() -> Element {
+    let mut clock = use_resource(get_lobby_clock);
+    let mut frozen = use_signal(|| false);
+    let mut offset = use_signal(|| 0_i64);
+
+    use_effect(move || {
+        if let Some(Ok(clock)) = clock() {
+            frozen.set(clock.frozen);
+            offset.set(clock.offset);
+        }
+    });
+
+    rsx! {
+        div { class: "mt-3",
+            label { class: "block", {t!("lobby_clock_header")} }
+            {
+                load_gate!(
+                    clock(), clock => { let time = Utc.timestamp_millis_opt(clock.time as i64)
+                    .single(); if let Some(time) = time { rsx! { p { strong { { t!("lobby_status")
+                    } } "{time}" } } } else { rsx! { p { class : "text-error", {
+                    t!("error_invalid_response") } } } } }
+                )
+            }
+            label { class: "label cursor-pointer justify-start gap-3 mt-1",
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-primary",
+                    checked: frozen(),
+                    onchange: move |event| frozen.set(event.checked()),
+                }
+                span { {t!("lobby_clock_frozen")} }
+            }
+            label { class: "block mt-1", {t!("lobby_clock_offset")} }
+            div { class: "flex gap-2",
+                input {
+                    r#type: "number",
+                    class: "input input-bordered w-full",
+                    value: offset(),
+                    oninput: move |event| {
+                        if let Ok(value) = event.value().parse() {
+                            offset.set(value);
+                        }
+                    },
+                }
+                button {
+                    class: "btn btn-primary",
+                    onclick: move |_| async move {
+                        if set_lobby_clock(frozen(), offset()).await.is_ok() {
+                            clock.restart();
+                        }
+                    },
+                    {t!("lobby_clock_save")}
+                }
+            }
+        }
+    }
+}
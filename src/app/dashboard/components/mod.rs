@@ -1,8 +1,14 @@
 pub mod address;
+pub mod backups;
+pub mod client_config;
+pub mod client_versions;
 pub mod config;
+pub mod currency;
 pub mod dlc;
 pub mod donuts;
 pub mod events;
+pub mod friends;
+pub mod gameplay_config;
 pub mod health;
 pub mod lobby;
 pub mod navbar;
@@ -29,6 +29,7 @@ pub fn Navbar() -> Element {
             }
             if is_operator {
                 Link { to: Route::Logs {}, {t!("route_logs")} }
+                Link { to: Route::Players {}, {t!("route_players")} }
             }
             Link { to: Route::Credits {}, {t!("route_credits")} }
         }
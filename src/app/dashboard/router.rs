@@ -13,6 +13,8 @@ pub enum Route {
     Login {},
     #[route("/logs")]
     Logs {},
+    #[route("/players")]
+    Players {},
     #[route("/:..segments")]
     NotFound { segments: Vec<String>}
 }
@@ -1,6 +1,8 @@
 use crate::app::models::{
     auth::{Role, User},
+    currency::CurrencyTransaction,
     dashboard::*,
+    friend::FriendsResponse,
 };
 #[cfg(feature = "server")]
 use crate::{
@@ -51,7 +53,7 @@ pub async fn set_config(config: ServerConfigResponse) -> Result<ConfigResult, Se
         let set_port = DashboardController::set_port(config.port);
         let set_default_donuts = DashboardController::set_default_donuts(config.default_donuts);
         let set_dlc_path = DashboardController::set_dlc_path(config.dlc_folder);
-        let set_event = DashboardController::set_event(config.current_event);
+        let set_event = DashboardController::default().set_event(config.current_event);
 
         Ok((
             set_address.err().map(|e| format!("{e}")),
@@ -63,10 +65,31 @@ pub async fn set_config(config: ServerConfigResponse) -> Result<ConfigResult, Se
     });
 }
 
+#[server]
+pub async fn get_client_versions() -> Result<ClientVersionsResponse, ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().get_client_versions())
+    });
+}
+
+#[server]
+pub async fn set_client_version_policy(policy: ClientVersionPolicy) -> Result<(), ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().set_client_version_policy(policy)?)
+    });
+}
+
+#[server]
+pub async fn reload_config() -> Result<ConfigReloadResponse, ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::reload_config()?)
+    });
+}
+
 #[server]
 pub async fn get_events() -> Result<EventsResponse, ServerFnError> {
     require_auth!(Role::Operator, session => {
-        Ok(DashboardController::get_events())
+        Ok(DashboardController::default().get_events()?)
     });
 }
 
@@ -80,7 +103,91 @@ pub async fn get_event() -> Result<(u64, String), ServerFnError> {
 #[server]
 pub async fn set_event(ts: u64) -> Result<(), ServerFnError> {
     require_auth!(Role::Operator, session => {
-        Ok(DashboardController::set_event(ts)?)
+        Ok(DashboardController::default().set_event(ts)?)
+    });
+}
+
+#[server]
+pub async fn get_event_schedule() -> Result<EventScheduleResponse, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().get_event_schedule()?)
+    });
+}
+
+#[server]
+pub async fn set_event_rotation(enabled: bool, interval: u64) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().set_event_rotation(enabled, interval)?)
+    });
+}
+
+#[server]
+pub async fn set_event_playlist(playlist: Vec<PlaylistEntry>) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().set_event_playlist(playlist)?)
+    });
+}
+
+#[server]
+pub async fn next_event() -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().next_event()?)
+    });
+}
+
+#[server]
+pub async fn get_event_catalog() -> Result<Vec<CatalogEvent>, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().get_event_catalog()?)
+    });
+}
+
+#[server]
+pub async fn save_catalog_event(event: CatalogEvent) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().save_catalog_event(event)?)
+    });
+}
+
+#[server]
+pub async fn remove_catalog_event(ts: u64) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().remove_catalog_event(ts)?)
+    });
+}
+
+#[server]
+pub async fn get_client_config() -> Result<Vec<ConfigEntry>, ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().get_client_config()?)
+    });
+}
+
+#[server]
+pub async fn set_client_config_item(
+    name: String,
+    value: Option<String>,
+) -> Result<(), ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().set_client_config_item(name, value)?)
+    });
+}
+
+#[server]
+pub async fn get_gameplay_config(event: Option<u64>) -> Result<Vec<ConfigEntry>, ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().get_gameplay_config(event)?)
+    });
+}
+
+#[server]
+pub async fn set_gameplay_config_item(
+    event: Option<u64>,
+    name: String,
+    value: Option<String>,
+) -> Result<(), ServerFnError> {
+    require_auth!(Role::Owner, session => {
+        Ok(DashboardController::default().set_gameplay_config_item(event, name, value)?)
     });
 }
 
@@ -112,6 +219,20 @@ pub async fn get_lobby_time() -> Result<u128, ServerFnError> {
     });
 }
 
+#[server]
+pub async fn get_lobby_clock() -> Result<LobbyClockResponse, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::get_lobby_clock()?)
+    });
+}
+
+#[server]
+pub async fn set_lobby_clock(frozen: bool, offset: i64) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        Ok(DashboardController::default().set_lobby_clock(frozen, offset)?)
+    });
+}
+
 #[server]
 pub async fn get_players() -> Result<u64, ServerFnError> {
     require_auth!(Role::User, session => {
@@ -120,6 +241,122 @@ pub async fn get_players() -> Result<u64, ServerFnError> {
     });
 }
 
+#[server]
+pub async fn get_player_list() -> Result<Vec<Player>, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_player_list().await?)
+    });
+}
+
+#[server]
+pub async fn set_matchmaking_opt_out(
+    mayhem_id: String,
+    opt_out: bool,
+) -> Result<bool, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.set_matchmaking_opt_out(mayhem_id, opt_out).await?)
+    });
+}
+
+#[server]
+pub async fn get_land_backups(mayhem_id: String) -> Result<Vec<u64>, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_land_backups(mayhem_id).await?)
+    });
+}
+
+#[server]
+pub async fn restore_land_backup(mayhem_id: String, date: u64) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.restore_land_backup(mayhem_id, date).await?)
+    });
+}
+
+#[server]
+pub async fn get_currency_transactions(
+    mayhem_id: String,
+    page: u32,
+) -> Result<Vec<CurrencyTransaction>, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_currency_transactions(mayhem_id, page).await?)
+    });
+}
+
+#[server]
+pub async fn get_event_override(mayhem_id: String) -> Result<Option<EventOverride>, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_event_override(mayhem_id).await?)
+    });
+}
+
+#[server]
+pub async fn set_event_override(
+    mayhem_id: String,
+    event_override: Option<EventOverride>,
+) -> Result<(), ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.set_event_override(mayhem_id, event_override).await?)
+    });
+}
+
+#[server]
+pub async fn get_friends(mayhem_id: String) -> Result<FriendsResponse, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_friends(mayhem_id).await?)
+    });
+}
+
+#[server]
+pub async fn get_friend_code(mayhem_id: String) -> Result<String, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.get_friend_code(mayhem_id).await?)
+    });
+}
+
+#[server]
+pub async fn regenerate_friend_code(mayhem_id: String) -> Result<String, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.regenerate_friend_code(mayhem_id).await?)
+    });
+}
+
+#[server]
+pub async fn redeem_friend_code(mayhem_id: String, code: String) -> Result<String, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.redeem_friend_code(mayhem_id, code).await?)
+    });
+}
+
+#[server]
+pub async fn accept_friend_request(
+    mayhem_id: String,
+    requester: String,
+) -> Result<bool, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.accept_friend_request(mayhem_id, requester).await?)
+    });
+}
+
+#[server]
+pub async fn remove_friend(mayhem_id: String, friend_id: String) -> Result<bool, ServerFnError> {
+    require_auth!(Role::Operator, session => {
+        extract!(controller: DashboardController);
+        Ok(controller.remove_friend(mayhem_id, friend_id).await?)
+    });
+}
+
 #[server]
 pub async fn get_role() -> Result<Role, ServerFnError> {
     require_auth!(session => {
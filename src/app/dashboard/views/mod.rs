@@ -12,3 +12,6 @@ pub use login::Login;
 
 mod not_found;
 pub use not_found::NotFound;
+
+mod players;
+pub use players::Players;
@@ -0,0 +1,99 @@
+use crate::{
+    app::{
+        dashboard::{
+            components::{
+                backups::BackupSection, currency::CurrencySection, events::EventOverrideSection,
+                friends::FriendSection,
+            },
+            providers::use_loggedin,
+            router::Route,
+            server::{get_player_list, set_matchmaking_opt_out},
+        },
+        models::dashboard::Player,
+    },
+    load_gate,
+};
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+#[component]
+pub fn Players() -> Element {
+    let logged_in = use_loggedin();
+    let players = use_resource(get_player_list);
+    let mut selected: Signal<Option<String>> = use_signal(|| None);
+    let on_select = use_callback(move |mayhem_id: String| selected.set(Some(mayhem_id)));
+
+    // workaround if auth state changes because browser router does not trigger reloads
+    if !logged_in {
+        let navigator = navigator();
+        navigator.push(Route::Login {});
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "body-container md:grid-cols-2",
+            div { class: "p-6 overflow-scroll",
+                h2 { class: "text-lg font-semibold", {t!("players_header")} }
+                {
+                    load_gate!(
+                        players(), players => { rsx! { table { class : "table mt-3", thead { tr {
+                        th { { t!("players_mayhem_id") } } th { { t!("players_name") } } th { { t!("players_matchmaking") } } th {} } }
+                        tbody { for player in players { PlayerRow { player, on_select } } } } } }
+                    )
+                }
+            }
+
+            if let Some(mayhem_id) = selected() {
+                div {
+                    BackupSection { mayhem_id: mayhem_id.clone() }
+                    CurrencySection { mayhem_id: mayhem_id.clone() }
+                    FriendSection { mayhem_id: mayhem_id.clone() }
+                    EventOverrideSection { mayhem_id }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PlayerRow(player: Player, on_select: Callback<String>) -> Element {
+    let mayhem_id = player.mayhem_id.clone();
+    let name = player.name.or(player.email).unwrap_or_default();
+    let mut matchmaking = use_signal(|| !player.matchmaking_opt_out);
+    let onchange = {
+        let mayhem_id = mayhem_id.clone();
+
+        move |event: Event<FormData>| {
+            let mayhem_id = mayhem_id.clone();
+            let enabled = event.checked();
+
+            async move {
+                if let Ok(true) = set_matchmaking_opt_out(mayhem_id, !enabled).await {
+                    matchmaking.set(enabled);
+                }
+            }
+        }
+    };
+
+    rsx! {
+        tr {
+            td { "{player.mayhem_id}" }
+            td { "{name}" }
+            td {
+                input {
+                    r#type: "checkbox",
+                    class: "toggle toggle-primary",
+                    checked: matchmaking(),
+                    onchange,
+                }
+            }
+            td {
+                button {
+                    class: "btn btn-primary btn-sm",
+                    onclick: move |_| on_select.call(mayhem_id.clone()),
+                    {t!("players_select")}
+                }
+            }
+        }
+    }
+}
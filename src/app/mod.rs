@@ -14,6 +14,7 @@ mod r#mod {
             auth::{AuthController, Session},
             dashboard::DashboardController,
             direction::DirectionController,
+            friend::FriendController,
             mayhem::MayhemController,
             proxy::ProxyController,
             user::UserController,
@@ -36,7 +37,7 @@ mod r#mod {
     use tokio::net::TcpListener;
     use tracing::{debug, instrument};
 
-    const PROTECTED_ROUTES: &[&str] = &["/", "/logs"];
+    const PROTECTED_ROUTES: &[&str] = &["/", "/logs", "/players"];
 
     pub async fn create_router() -> anyhow::Result<Router> {
         use crate::app::dashboard::App;
@@ -66,6 +67,7 @@ mod r#mod {
             .layer(Extension(UserController::default()))
             .layer(Extension(DashboardController::default()))
             .layer(Extension(DirectionController))
+            .layer(Extension(FriendController::default()))
             .layer(Extension(MayhemController::default()))
             .layer(Extension(ProxyController::default()))
             .layer(Extension(UserController::default()))
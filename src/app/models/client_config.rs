@@ -0,0 +1,9 @@
+use std::collections::BTreeMap;
+
+/// content of `client_config.toml` in the config dir, applied onto the embedded `ClientConfig.json`
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ClientConfigToml {
+    /// values by item name, items missing in `ClientConfig.json` are added
+    #[serde(default)]
+    pub items: BTreeMap<String, String>,
+}
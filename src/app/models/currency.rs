@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "server",
+    derive(sqlx::Type),
+    sqlx(type_name = "TEXT", rename_all = "lowercase")
+)]
+pub enum CurrencySource {
+    /// donuts a save got created with
+    Initial,
+    /// deltas reported by the client in `extraLandUpdate`
+    Client,
+    /// donut packs bought in the store
+    Purchase,
+    /// costs and prizes of the mystery box
+    Gamble,
+    /// corrections for saves changed outside of the ledger
+    Reconcile,
+}
+
+impl std::fmt::Display for CurrencySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CurrencySource::Initial => "initial",
+            CurrencySource::Client => "client",
+            CurrencySource::Purchase => "purchase",
+            CurrencySource::Gamble => "gamble",
+            CurrencySource::Reconcile => "reconcile",
+        })
+    }
+}
+
+/// amount of transactions the dashboard lists at once
+pub const CURRENCY_TRANSACTIONS_PER_PAGE: u32 = 50;
+
+/// a single change of the donut balance of a player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(FromRow))]
+pub struct CurrencyTransaction {
+    pub id: i64,
+    pub mayhem_id: String,
+    pub source: CurrencySource,
+    /// reason reported by the client or a short description of the server side change
+    pub reason: Option<String>,
+    pub amount: i64,
+    pub balance_before: i64,
+    pub balance_after: i64,
+    /// groups all transactions which were applied by the same request
+    pub request_id: String,
+    pub created_at: i64,
+}
+
+impl CurrencyTransaction {
+    pub fn new(
+        mayhem_id: impl AsRef<str>,
+        source: CurrencySource,
+        reason: Option<String>,
+        amount: i64,
+        balance_before: i64,
+        request_id: impl AsRef<str>,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            id: 0,
+            mayhem_id: mayhem_id.as_ref().to_owned(),
+            source,
+            reason,
+            amount,
+            balance_before,
+            balance_after: balance_before + amount,
+            request_id: request_id.as_ref().to_owned(),
+            created_at,
+        }
+    }
+}
@@ -45,6 +45,151 @@ pub struct ServerConfigResponse {
     pub current_event: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PlaylistEntry {
+    /// start timestamp of the event, see the event catalog
+    pub event: u64,
+    /// seconds the event stays selected, the rotation interval if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+}
+
+/// entry of the event catalog in `events.toml`
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct CatalogEvent {
+    /// unix timestamp in seconds the event started at, identifies the event
+    pub start: u64,
+    pub name: String,
+    /// seconds the event lasted, used by the rotation if the playlist does not set one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    /// dlc packages the event needs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ScheduledEvent {
+    pub event: u64,
+    pub name: String,
+    /// unix timestamp in seconds the event gets selected at
+    pub starts_at: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EventScheduleResponse {
+    pub enabled: bool,
+    /// seconds an event stays selected by default
+    pub interval: u64,
+    pub playlist: Vec<PlaylistEntry>,
+    pub upcoming: Vec<ScheduledEvent>,
+}
+
+/// event and lobby clock of a single player, replacing the global ones
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EventOverride {
+    /// start timestamp of the event, see the event catalog
+    pub event: u64,
+    pub frozen: bool,
+    /// seconds added to the clock
+    pub offset: i64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct LobbyClockResponse {
+    /// current lobby time in milliseconds
+    pub time: u128,
+    pub frozen: bool,
+    /// seconds added to the clock
+    pub offset: i64,
+}
+
+/// item of the client or gameplay config
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ConfigEntry {
+    pub name: String,
+    /// value without the override, unset for items only the override file has
+    pub default: Option<String>,
+    /// value of the override file, unset if the item is not overridden
+    pub value: Option<String>,
+}
+
+/// settings which changed when the config files were read again
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ConfigReloadResponse {
+    /// settings which are live already
+    pub applied: Vec<String>,
+    /// settings which differ from the ones the server started with and only apply after a restart
+    pub restart_required: Vec<String>,
+}
+
+/// client builds which may connect, `client_versions` in `server.toml`
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ClientVersionPolicy {
+    /// rules of platforms without their own rules
+    #[serde(default)]
+    pub default: VersionRules,
+    /// rules by platform, e.g. `android`, `android.amazon` or `ios`
+    #[serde(default)]
+    pub platforms: std::collections::BTreeMap<String, VersionRules>,
+}
+
+impl ClientVersionPolicy {
+    pub fn rules(&self, platform: Option<&str>) -> &VersionRules {
+        platform
+            .and_then(|platform| {
+                self.platforms
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(platform))
+            })
+            .map_or(&self.default, |(_, rules)| rules)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct VersionRules {
+    /// older clients are rejected
+    pub minimum: Option<String>,
+    /// older clients are asked to upgrade
+    pub current: Option<String>,
+    /// versions which may connect even if they are older than `minimum`
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum VersionStatus {
+    Allowed,
+    UpgradeAvailable,
+    Rejected,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ClientVersionsResponse {
+    pub policy: ClientVersionPolicy,
+    pub seen: Vec<SeenClientVersion>,
+}
+
+/// client version which connected since the server started
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SeenClientVersion {
+    pub platform: Option<String>,
+    pub version: String,
+    /// unix timestamp in seconds
+    pub last_seen: u64,
+    /// status under the current policy
+    pub status: VersionStatus,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Player {
+    pub mayhem_id: String,
+    pub user_id: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub matchmaking_opt_out: bool,
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
 pub struct EventsResponse {
     pub events: Vec<(u64, String)>,
@@ -23,6 +23,9 @@ pub struct Direction {
     pub server_api_version: String,
     pub server_data: Vec<KVPair>,
     pub telemetry_freq: usize,
+    /// directions of specific platforms or packages, see `DirectionProfileToml`
+    #[serde(skip)]
+    pub profiles: Vec<DirectionProfile>,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
@@ -48,6 +51,69 @@ pub struct DirectionToml {
     pub server_api_version: Option<String>,
     pub server_data: Option<HashMap<String, String>>,
     pub telemetry_freq: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<DirectionProfileToml>,
+}
+
+/// overrides of `[[profiles]]` in `directions.toml` for requests of a platform and/or package
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectionProfileToml {
+    /// platform of the request, e.g. `android` or `ios`. matches every platform if unset
+    pub platform: Option<String>,
+    /// package id on android or bundle id on ios. matches every package if unset
+    pub package_id: Option<String>,
+    pub client_id: Option<String>,
+    pub mdm_app_key: Option<String>,
+    pub disabled_features: Option<Vec<String>>,
+    pub poll_intervals: Option<HashMap<String, String>>,
+    pub server_data: Option<HashMap<String, String>>,
+}
+
+/// profile of `directions.toml` with server addresses filled in
+#[derive(Debug, Default, serde::Serialize, Clone)]
+pub struct DirectionProfile {
+    pub platform: Option<String>,
+    pub package_id: Option<String>,
+    pub client_id: Option<String>,
+    pub mdm_app_key: Option<String>,
+    pub disabled_features: Option<Vec<String>>,
+    pub poll_intervals: Option<Vec<KVPair>>,
+    pub server_data: Option<Vec<KVPair>>,
+}
+
+impl DirectionProfile {
+    /// how well the profile fits a request, `None` if it does not apply.
+    ///
+    /// a matching package weighs more than a matching platform
+    pub fn score(&self, platform: &str, package_id: &str) -> Option<u8> {
+        let platform = match &self.platform {
+            Some(p) if p.eq_ignore_ascii_case(platform) => 1,
+            Some(_) => return None,
+            None => 0,
+        };
+        let package = match &self.package_id {
+            Some(p) if p == package_id => 2,
+            Some(_) => return None,
+            None => 0,
+        };
+
+        Some(platform + package)
+    }
+
+    /// the most specific profile of `profiles` for a request, the first one if several fit equally well
+    pub fn select<'a>(
+        profiles: &'a [DirectionProfile],
+        platform: &str,
+        package_id: &str,
+    ) -> Option<&'a DirectionProfile> {
+        profiles
+            .iter()
+            .filter_map(|profile| Some((profile.score(platform, package_id)?, profile)))
+            .rev()
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, profile)| profile)
+    }
 }
 
 impl Default for DirectionToml {
@@ -78,6 +144,7 @@ impl Default for DirectionToml {
             sell_id: None,
             server_api_version: None,
             server_data: None,
+            profiles: vec![],
         }
     }
 }
@@ -110,3 +177,85 @@ where
 }
 
 impl HashMapToVec<String, String, KVPair> for Vec<KVPair> {}
+
+#[cfg(test)]
+mod tests {
+    use super::DirectionProfile;
+
+    fn profile(platform: Option<&str>, package_id: Option<&str>) -> DirectionProfile {
+        DirectionProfile {
+            platform: platform.map(str::to_owned),
+            package_id: package_id.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn score_of_a_profile_without_filters() {
+        assert_eq!(profile(None, None).score("android", "com.ea.game"), Some(0));
+    }
+
+    #[test]
+    fn score_ignores_the_case_of_the_platform() {
+        assert_eq!(
+            profile(Some("Android"), None).score("android", "com.ea.game"),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn score_prefers_the_package_over_the_platform() {
+        let platform = profile(Some("android"), None).score("android", "com.ea.game");
+        let package = profile(None, Some("com.ea.game")).score("android", "com.ea.game");
+        let both = profile(Some("android"), Some("com.ea.game")).score("android", "com.ea.game");
+
+        assert!(package > platform);
+        assert!(both > package);
+    }
+
+    #[test]
+    fn score_of_a_profile_for_another_client() {
+        assert_eq!(
+            profile(Some("ios"), None).score("android", "com.ea.game"),
+            None
+        );
+        assert_eq!(
+            profile(Some("android"), Some("com.ea.other")).score("android", "com.ea.game"),
+            None
+        );
+    }
+
+    #[test]
+    fn select_picks_the_most_specific_profile() {
+        let profiles = [
+            profile(None, None),
+            profile(Some("android"), None),
+            profile(Some("android"), Some("com.ea.game")),
+            profile(Some("ios"), Some("com.ea.game")),
+        ];
+
+        let selected = DirectionProfile::select(&profiles, "android", "com.ea.game");
+
+        assert!(std::ptr::eq(selected.unwrap(), &profiles[2]));
+    }
+
+    #[test]
+    fn select_picks_the_first_of_equally_specific_profiles() {
+        let profiles = [
+            profile(Some("ios"), None),
+            profile(Some("android"), None),
+            profile(Some("ANDROID"), None),
+        ];
+
+        let selected = DirectionProfile::select(&profiles, "android", "com.ea.game");
+
+        assert!(std::ptr::eq(selected.unwrap(), &profiles[1]));
+    }
+
+    #[test]
+    fn select_without_a_fitting_profile() {
+        let profiles = [profile(Some("ios"), None)];
+
+        assert!(DirectionProfile::select(&profiles, "android", "com.ea.game").is_none());
+    }
+}
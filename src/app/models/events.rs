@@ -1,3 +1,59 @@
+use super::dashboard::{CatalogEvent, PlaylistEntry};
+use std::collections::HashSet;
+
+/// content of `playlist.toml` in the config dir, replaces rotating through the whole event catalog
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Playlist {
+    #[serde(default)]
+    pub events: Vec<PlaylistEntry>,
+}
+
+/// content of `events.toml` in the config dir, replaces the built-in `TSTO_EVENTS`
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct EventCatalog {
+    #[serde(default)]
+    pub events: Vec<CatalogEvent>,
+}
+
+impl EventCatalog {
+    /// catalog of the events the original server knew
+    pub fn builtin() -> Self {
+        Self {
+            events: TSTO_EVENTS
+                .iter()
+                .map(|(start, name)| CatalogEvent {
+                    start: *start,
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    /// checks that every event has a name and a unique start and that normal play exists
+    pub fn validate(&self) -> Result<(), String> {
+        let mut starts = HashSet::new();
+
+        for event in &self.events {
+            if event.name.trim().is_empty() {
+                return Err(format!("event {} has no name", event.start));
+            }
+            if !starts.insert(event.start) {
+                return Err(format!("event {} exists more than once", event.start));
+            }
+            if event.duration == Some(0) {
+                return Err(format!("event {} has a duration of 0", event.start));
+            }
+        }
+
+        if !starts.contains(&0) {
+            return Err("normal play with timestamp 0 is missing".to_string());
+        }
+
+        Ok(())
+    }
+}
+
 pub static TSTO_EVENTS: &[(u64, &str)] = &[
     (0, "Normal Play"),
     (1348228800, "The first new level : Level 21"),
@@ -311,3 +367,77 @@ pub static TSTO_EVENTS: &[(u64, &str)] = &[
     (1723032000, "Event : A Bart Future"),
     (1727352000, "Final Event : Taps"),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogEvent, EventCatalog};
+
+    fn event(start: u64, name: &str) -> CatalogEvent {
+        CatalogEvent {
+            start,
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn catalog(events: Vec<CatalogEvent>) -> EventCatalog {
+        EventCatalog { events }
+    }
+
+    #[test]
+    fn builtin_is_valid() {
+        assert_eq!(EventCatalog::builtin().validate(), Ok(()));
+    }
+
+    #[test]
+    fn requires_normal_play() {
+        let catalog = catalog(vec![event(1349265600, "Treehouse of Horror XXIII")]);
+
+        assert!(catalog.validate().is_err());
+        assert!(EventCatalog::default().validate().is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_starts() {
+        let catalog = catalog(vec![
+            event(0, "Normal Play"),
+            event(1349265600, "Treehouse of Horror XXIII"),
+            event(1349265600, "Level 22"),
+        ]);
+
+        assert!(catalog.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_blank_names() {
+        let catalog = catalog(vec![event(0, "Normal Play"), event(1349265600, "  ")]);
+
+        assert!(catalog.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_a_duration_of_zero() {
+        let catalog = catalog(vec![
+            event(0, "Normal Play"),
+            CatalogEvent {
+                duration: Some(0),
+                ..event(1349265600, "Treehouse of Horror XXIII")
+            },
+        ]);
+
+        assert!(catalog.validate().is_err());
+    }
+
+    #[test]
+    fn accepts_events_with_a_duration() {
+        let catalog = catalog(vec![
+            event(0, "Normal Play"),
+            CatalogEvent {
+                duration: Some(604800),
+                ..event(1349265600, "Treehouse of Horror XXIII")
+            },
+        ]);
+
+        assert_eq!(catalog.validate(), Ok(()));
+    }
+}
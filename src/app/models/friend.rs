@@ -0,0 +1,25 @@
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Friend {
+    pub mayhem_id: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FriendsResponse {
+    pub friends: Vec<Friend>,
+    /// requests waiting to be accepted by the user
+    pub requests: Vec<Friend>,
+    /// requests the user sent to others
+    pub sent_requests: Vec<Friend>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FriendCodeResponse {
+    pub code: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct FriendRequestResponse {
+    pub friend_id: String,
+    pub accepted: bool,
+}
@@ -0,0 +1,30 @@
+use std::collections::BTreeMap;
+
+/// content of `gameplay_config.toml` in the config dir, applied onto the embedded `GameplayConfig.json`
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GameplayConfigToml {
+    /// values by item name, items missing in `GameplayConfig.json` are added
+    #[serde(default)]
+    pub items: BTreeMap<String, String>,
+    /// items which only apply while their event is selected, on top of `items`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<EventGameplayToml>,
+}
+
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct EventGameplayToml {
+    /// start timestamp of the event, see the event catalog
+    pub event: u64,
+    #[serde(default)]
+    pub items: BTreeMap<String, String>,
+}
+
+impl GameplayConfigToml {
+    /// items of `event`, if there are any
+    pub fn event_items(&self, event: u64) -> Option<&BTreeMap<String, String>> {
+        self.events
+            .iter()
+            .find(|overlay| overlay.event == event)
+            .map(|overlay| &overlay.items)
+    }
+}
@@ -1,12 +1,22 @@
 pub mod auth;
+#[cfg(feature = "server")]
+pub mod client_config;
+pub mod currency;
 pub mod dashboard;
 #[cfg(feature = "server")]
 pub mod direction;
 #[cfg(feature = "server")]
 pub mod events;
+pub mod friend;
+#[cfg(feature = "server")]
+pub mod gameplay_config;
+#[cfg(feature = "server")]
+pub mod offers;
 #[cfg(feature = "server")]
 pub mod proxy;
 #[cfg(feature = "server")]
+pub mod purchase;
+#[cfg(feature = "server")]
 pub mod tracking;
 #[cfg(feature = "server")]
 pub mod user;
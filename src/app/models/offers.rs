@@ -0,0 +1,161 @@
+use crate::protos::data::offers_response_message::{
+    level_up_xp_offer::LevelUpRecordType,
+    offer_item::{OfferType, RecommendationType},
+    price_override_offer::{CurrencyPriceType, OverrideItemType},
+    LevelUpXpOffer, OfferItem, PriceOverrideOffer,
+};
+
+/// content of `offers.toml` in the config dir
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OffersToml {
+    #[serde(default)]
+    pub offers: Vec<OfferToml>,
+    #[serde(default)]
+    pub price_overrides: Vec<PriceOverrideToml>,
+    #[serde(default)]
+    pub level_up_offers: Vec<LevelUpOfferToml>,
+}
+
+/// limits an entry to an event and/or a time span. entries without any limit are always active
+#[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OfferWindow {
+    /// start timestamp of the event the entry belongs to, see the event catalog
+    pub event: Option<u64>,
+    /// unix timestamp in seconds the entry becomes active
+    pub start: Option<u64>,
+    /// unix timestamp in seconds the entry expires
+    pub end: Option<u64>,
+}
+
+impl OfferWindow {
+    pub fn is_active(&self, event: u64, now: u64) -> bool {
+        self.event.is_none_or(|e| e == event)
+            && self.start.is_none_or(|start| start <= now)
+            && self.end.is_none_or(|end| now < end)
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OfferToml {
+    pub product_id: String,
+    pub offer_type: OfferTypeToml,
+    pub recommendation: Option<RecommendationToml>,
+    #[serde(flatten)]
+    pub window: OfferWindow,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OfferTypeToml {
+    Mtx,
+    Premium,
+    Character,
+    Building,
+    Consumable,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecommendationToml {
+    Recommend,
+    Watch,
+    NoConvert,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PriceOverrideToml {
+    pub product_id: String,
+    pub item_type: OverrideItemTypeToml,
+    /// currency the item is sold for, donuts unless set otherwise
+    #[serde(default)]
+    pub currency: CurrencyPriceToml,
+    pub amount: i32,
+    #[serde(flatten)]
+    pub window: OfferWindow,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverrideItemTypeToml {
+    Character,
+    Building,
+    Consumable,
+}
+
+#[derive(Debug, Default, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CurrencyPriceToml {
+    /// in-game money
+    Grind,
+    /// donuts
+    #[default]
+    Premium,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct LevelUpOfferToml {
+    pub level: i32,
+    pub xp_for_next_level: Option<i32>,
+    #[serde(default)]
+    pub donuts: i32,
+    #[serde(default)]
+    pub money: i32,
+    #[serde(flatten)]
+    pub window: OfferWindow,
+}
+
+impl From<&OfferToml> for OfferItem {
+    fn from(value: &OfferToml) -> Self {
+        let offer_type = match value.offer_type {
+            OfferTypeToml::Mtx => OfferType::Mtx,
+            OfferTypeToml::Premium => OfferType::Premium,
+            OfferTypeToml::Character => OfferType::Character,
+            OfferTypeToml::Building => OfferType::Building,
+            OfferTypeToml::Consumable => OfferType::Consumable,
+        };
+        let recommendation_type = value.recommendation.map(|r| match r {
+            RecommendationToml::Recommend => RecommendationType::Recommend,
+            RecommendationToml::Watch => RecommendationType::Watch,
+            RecommendationToml::NoConvert => RecommendationType::Noconvert,
+        });
+
+        Self {
+            offer_type: Some(offer_type as i32),
+            product_id: Some(value.product_id.clone()),
+            recommendation_type: recommendation_type.map(|r| r as i32),
+        }
+    }
+}
+
+impl From<&PriceOverrideToml> for PriceOverrideOffer {
+    fn from(value: &PriceOverrideToml) -> Self {
+        let override_item_type = match value.item_type {
+            OverrideItemTypeToml::Character => OverrideItemType::Character,
+            OverrideItemTypeToml::Building => OverrideItemType::Building,
+            OverrideItemTypeToml::Consumable => OverrideItemType::Consumable,
+        };
+        let currency_price_type = match value.currency {
+            CurrencyPriceToml::Grind => CurrencyPriceType::Grind,
+            CurrencyPriceToml::Premium => CurrencyPriceType::Premium,
+        };
+
+        Self {
+            override_item_type: Some(override_item_type as i32),
+            product_id: Some(value.product_id.clone()),
+            currency_price_type: Some(currency_price_type as i32),
+            currency_amount: Some(value.amount),
+        }
+    }
+}
+
+impl From<&LevelUpOfferToml> for LevelUpXpOffer {
+    fn from(value: &LevelUpOfferToml) -> Self {
+        Self {
+            level: Some(value.level),
+            xp_for_next_level: value.xp_for_next_level,
+            donut_award_amount: Some(value.donuts),
+            money_award_amount: Some(value.money),
+            level_up_record_type: Some(LevelUpRecordType::Offer as i32),
+        }
+    }
+}
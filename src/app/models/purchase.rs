@@ -0,0 +1,354 @@
+use crate::protos::data::{PurchasePlatform, PurchaseRequestMessage};
+use base64::{engine::general_purpose, Engine};
+
+/// purchase details the store signs and the client forwards.
+///
+/// google play and amazon send them as json, itunes as base64 encoded receipt
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedPurchase {
+    #[serde(alias = "receiptId")]
+    pub order_id: Option<String>,
+    #[serde(alias = "sku")]
+    pub product_id: Option<String>,
+    pub purchase_token: Option<String>,
+}
+
+impl SignedPurchase {
+    /// reads the purchase of `request` from where its platform puts it.
+    ///
+    /// unified itunes receipts are binary, so `skus` are looked up in them
+    pub fn parse<'a>(
+        request: &PurchaseRequestMessage,
+        skus: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let receipt = request.receipt.as_deref();
+        let purchase = match request
+            .platform
+            .and_then(|platform| PurchasePlatform::try_from(platform).ok())
+        {
+            Some(PurchasePlatform::Itunes) => Self::itunes(receipt),
+            Some(PurchasePlatform::ItunesGrandUnifiedReceipt) => {
+                Self::unified_receipt(receipt, skus)
+            }
+            Some(PurchasePlatform::Amazon | PurchasePlatform::AmazonV2) => Self::json(receipt),
+            _ => Self::default(),
+        };
+
+        if purchase.product_id.is_some() {
+            return purchase;
+        }
+
+        // google play signs json, which other platforms may send as well
+        Self::json(request.signed_data.as_deref())
+    }
+
+    /// stable id of a purchase without order id, so retries of the same receipt are recognized.
+    ///
+    /// `None` if the request carries no receipt at all
+    pub fn receipt_id(request: &PurchaseRequestMessage) -> Option<String> {
+        use sha2::{Digest, Sha256};
+
+        let parts = [
+            &request.signed_data,
+            &request.receipt,
+            &request.purchase_token,
+        ];
+        if parts
+            .iter()
+            .all(|part| part.as_deref().unwrap_or_default().is_empty())
+        {
+            return None;
+        }
+
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part.as_deref().unwrap_or_default());
+            hasher.update(b"\0");
+        }
+
+        Some(format!("{:x}", hasher.finalize()))
+    }
+
+    fn json(data: Option<&str>) -> Self {
+        data.and_then(|data| serde_json::from_str(data).ok())
+            .unwrap_or_default()
+    }
+
+    /// classic receipts are an old-style plist holding another plist in `purchase-info`
+    fn itunes(receipt: Option<&str>) -> Self {
+        let Some(purchase_info) = receipt
+            .and_then(Self::decode)
+            .and_then(|receipt| {
+                Self::plist_value(&String::from_utf8_lossy(&receipt), "purchase-info")
+            })
+            .and_then(|purchase_info| Self::decode(&purchase_info))
+        else {
+            return Self::default();
+        };
+        let purchase_info = String::from_utf8_lossy(&purchase_info);
+
+        Self {
+            order_id: Self::plist_value(&purchase_info, "transaction-id"),
+            product_id: Self::plist_value(&purchase_info, "product-id"),
+            purchase_token: None,
+        }
+    }
+
+    /// unified receipts are ASN.1, product ids are stored as short `UTF8String`s.
+    ///
+    /// the receipt lists every unfinished purchase, so it is only used if exactly one sku is in it
+    fn unified_receipt<'a>(
+        receipt: Option<&str>,
+        skus: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        const UTF8_STRING: u8 = 0x0c;
+
+        let Some(receipt) = receipt.and_then(Self::decode) else {
+            return Self::default();
+        };
+
+        let mut found = skus.into_iter().filter(|sku| {
+            // lengths above 127 use the long form, which no sku needs
+            let Ok(len @ 0..=0x7f) = u8::try_from(sku.len()) else {
+                return false;
+            };
+            let needle = [&[UTF8_STRING, len][..], sku.as_bytes()].concat();
+
+            receipt.windows(needle.len()).any(|window| window == needle)
+        });
+
+        match (found.next(), found.next()) {
+            (Some(sku), None) => Self {
+                product_id: Some(sku.clone()),
+                ..Default::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    fn decode(data: &str) -> Option<Vec<u8>> {
+        general_purpose::STANDARD.decode(data.trim()).ok()
+    }
+
+    /// reads `"key" = "value";` of an old-style plist
+    fn plist_value(plist: &str, key: &str) -> Option<String> {
+        let (_, rest) = plist.split_once(&format!("\"{key}\" = \""))?;
+        let (value, _) = rest.split_once('"')?;
+
+        Some(value.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignedPurchase;
+    use crate::protos::data::{PurchasePlatform, PurchaseRequestMessage};
+    use base64::{engine::general_purpose, Engine};
+
+    const SKUS: [&str; 2] = ["com.ea.simpsons.donuts.1", "com.ea.simpsons.donuts.10"];
+
+    fn request(platform: PurchasePlatform, receipt: &str) -> PurchaseRequestMessage {
+        PurchaseRequestMessage {
+            platform: Some(platform.into()),
+            receipt: Some(receipt.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    fn parse(request: &PurchaseRequestMessage) -> SignedPurchase {
+        let skus = SKUS.map(str::to_owned);
+
+        SignedPurchase::parse(request, &skus)
+    }
+
+    fn encode(data: impl AsRef<[u8]>) -> String {
+        general_purpose::STANDARD.encode(data)
+    }
+
+    /// der encoded `UTF8String` as unified receipts store product ids
+    fn utf8_string(value: &str) -> Vec<u8> {
+        [&[0x0c, value.len() as u8][..], value.as_bytes()].concat()
+    }
+
+    #[test]
+    fn itunes_reads_the_purchase_info() {
+        let purchase_info = encode(
+            r#"{
+	"transaction-id" = "1000000123";
+	"product-id" = "com.ea.simpsons.donuts.10";
+	"quantity" = "1";
+}"#,
+        );
+        let receipt = encode(format!(
+            "{{\n\t\"signature\" = \"c2ln\";\n\t\"purchase-info\" = \"{purchase_info}\";\n}}"
+        ));
+
+        let purchase = parse(&request(PurchasePlatform::Itunes, &receipt));
+
+        assert_eq!(purchase.order_id.as_deref(), Some("1000000123"));
+        assert_eq!(
+            purchase.product_id.as_deref(),
+            Some("com.ea.simpsons.donuts.10")
+        );
+    }
+
+    #[test]
+    fn itunes_without_purchase_info_has_no_product() {
+        let receipt = encode(r#"{ "signature" = "c2ln"; }"#);
+
+        let purchase = parse(&request(PurchasePlatform::Itunes, &receipt));
+
+        assert_eq!(purchase.order_id, None);
+        assert_eq!(purchase.product_id, None);
+    }
+
+    #[test]
+    fn unified_receipt_finds_the_one_sku() {
+        let receipt = [
+            &[0x30, 0x82, 0x01, 0x00][..],
+            &utf8_string("com.ea.simpsons")[..],
+            &utf8_string("com.ea.simpsons.donuts.10")[..],
+            &[0x02, 0x01, 0x01][..],
+        ]
+        .concat();
+
+        let purchase = parse(&request(
+            PurchasePlatform::ItunesGrandUnifiedReceipt,
+            &encode(receipt),
+        ));
+
+        // `donuts.1` is a prefix of `donuts.10`, but its encoded length does not match
+        assert_eq!(
+            purchase.product_id.as_deref(),
+            Some("com.ea.simpsons.donuts.10")
+        );
+        assert_eq!(purchase.order_id, None);
+    }
+
+    #[test]
+    fn unified_receipt_with_several_skus_is_ambiguous() {
+        let receipt = [
+            utf8_string("com.ea.simpsons.donuts.1"),
+            utf8_string("com.ea.simpsons.donuts.10"),
+        ]
+        .concat();
+
+        let purchase = parse(&request(
+            PurchasePlatform::ItunesGrandUnifiedReceipt,
+            &encode(receipt),
+        ));
+
+        assert_eq!(purchase.product_id, None);
+    }
+
+    #[test]
+    fn unified_receipt_needs_encoded_skus() {
+        let receipt = b"com.ea.simpsons.donuts.10";
+
+        let purchase = parse(&request(
+            PurchasePlatform::ItunesGrandUnifiedReceipt,
+            &encode(receipt),
+        ));
+
+        assert_eq!(purchase.product_id, None);
+    }
+
+    #[test]
+    fn amazon_reads_the_receipt_json() {
+        let receipt = r#"{"receiptId":"q1YqVbJSyk","sku":"com.ea.simpsons.donuts.1"}"#;
+
+        let purchase = parse(&request(PurchasePlatform::AmazonV2, receipt));
+
+        assert_eq!(purchase.order_id.as_deref(), Some("q1YqVbJSyk"));
+        assert_eq!(
+            purchase.product_id.as_deref(),
+            Some("com.ea.simpsons.donuts.1")
+        );
+    }
+
+    #[test]
+    fn google_play_reads_the_signed_data() {
+        let request = PurchaseRequestMessage {
+            platform: Some(PurchasePlatform::GoogleplayV3.into()),
+            signed_data: Some(
+                r#"{"orderId":"GPA.1234","productId":"com.ea.simpsons.donuts.1","purchaseToken":"token"}"#
+                    .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        let purchase = parse(&request);
+
+        assert_eq!(purchase.order_id.as_deref(), Some("GPA.1234"));
+        assert_eq!(
+            purchase.product_id.as_deref(),
+            Some("com.ea.simpsons.donuts.1")
+        );
+        assert_eq!(purchase.purchase_token.as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn invalid_receipts_have_no_product() {
+        for platform in [
+            PurchasePlatform::Itunes,
+            PurchasePlatform::ItunesGrandUnifiedReceipt,
+            PurchasePlatform::Amazon,
+        ] {
+            let purchase = parse(&request(platform, "not a receipt"));
+
+            assert_eq!(purchase.order_id, None);
+            assert_eq!(purchase.product_id, None);
+        }
+
+        let purchase = parse(&PurchaseRequestMessage::default());
+
+        assert_eq!(purchase.order_id, None);
+        assert_eq!(purchase.product_id, None);
+    }
+
+    #[test]
+    fn receipt_id_needs_a_receipt() {
+        assert_eq!(
+            SignedPurchase::receipt_id(&PurchaseRequestMessage::default()),
+            None
+        );
+        assert_eq!(
+            SignedPurchase::receipt_id(&request(PurchasePlatform::Itunes, "")),
+            None
+        );
+    }
+
+    #[test]
+    fn receipt_id_is_stable_per_receipt() {
+        let first = SignedPurchase::receipt_id(&request(PurchasePlatform::Itunes, "receipt"));
+
+        assert!(first.is_some());
+        assert_eq!(
+            first,
+            SignedPurchase::receipt_id(&request(PurchasePlatform::Itunes, "receipt"))
+        );
+        assert_ne!(
+            first,
+            SignedPurchase::receipt_id(&request(PurchasePlatform::Itunes, "other receipt"))
+        );
+    }
+
+    #[test]
+    fn receipt_id_keeps_the_parts_apart() {
+        let joined = PurchaseRequestMessage {
+            signed_data: Some("ab".to_owned()),
+            ..Default::default()
+        };
+        let split = PurchaseRequestMessage {
+            signed_data: Some("a".to_owned()),
+            receipt: Some("b".to_owned()),
+            ..Default::default()
+        };
+
+        assert_ne!(
+            SignedPurchase::receipt_id(&joined),
+            SignedPurchase::receipt_id(&split)
+        );
+    }
+}
@@ -5,10 +5,10 @@ use crate::{
 };
 use axum::{
     extract::{Path, Query},
+    http::HeaderMap,
     routing::get,
     Json, Router,
 };
-use axum_response_cache::CacheLayer;
 use std::collections::HashMap;
 use tracing::{instrument, trace};
 
@@ -16,16 +16,17 @@ use tracing::{instrument, trace};
 pub fn create_router() -> Router {
     let router = Router::new()
         .route("/:platform/getDirectionByPackage", get(by_package))
-        .route("/:platform/getDirectionByBundle", get(by_bundle))
-        .layer(CacheLayer::with_lifespan(3600));
+        // not cached, directions change when `directions.toml` is reloaded
+        .route("/:platform/getDirectionByBundle", get(by_bundle));
 
     Router::new().nest("/api", router)
 }
 
-#[instrument]
+#[instrument(skip(headers))]
 async fn by_package(
     Path(platform): Path<String>,
     Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
 ) -> Result<Json<Direction>, Xml> {
     trace!("got director/by_package request");
     let package_id = query
@@ -33,19 +34,20 @@ async fn by_package(
         .ok_or(xml_response!("No packageId"))?;
 
     Ok(Json(
-        DirectionController::by_package(&platform, package_id).await,
+        DirectionController::by_package(&platform, package_id, &headers).await,
     ))
 }
 
-#[instrument]
+#[instrument(skip(headers))]
 async fn by_bundle(
     Path(platform): Path<String>,
     Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
 ) -> Result<Json<Direction>, Xml> {
     trace!("got director/by_bundle request");
     let bundle_id = query.get("bundleId").ok_or(xml_response!["No bundleId"])?;
 
     Ok(Json(
-        DirectionController::by_bundle(&platform, bundle_id).await,
+        DirectionController::by_bundle(&platform, bundle_id, &headers).await,
     ))
 }
@@ -0,0 +1,124 @@
+use crate::{
+    app::{
+        controllers::friend::{FriendController, FriendControllerError},
+        models::friend::{FriendCodeResponse, FriendRequestResponse, FriendsResponse},
+    },
+    util::{error::ErrorMessage, extractors::AccessToken},
+};
+use axum::{
+    extract::Path,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use tracing::trace;
+
+// /friends
+pub fn create_router() -> Router {
+    Router::new()
+        .route("/", get(friends))
+        .route("/code", get(code).post(regenerate_code))
+        .route("/redeem/:code", post(redeem))
+        .route("/request/:mayhem_id", post(request))
+        .route("/accept/:mayhem_id", post(accept))
+        .route("/:mayhem_id", delete(remove))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn friends(
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendsResponse>, ErrorMessage> {
+    trace!("got friends request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+
+    Ok(Json(controller.list(&mayhem_id).await?))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn code(
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendCodeResponse>, ErrorMessage> {
+    trace!("got friends/code request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+
+    Ok(Json(FriendCodeResponse {
+        code: controller.code(&mayhem_id).await?,
+    }))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn regenerate_code(
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendCodeResponse>, ErrorMessage> {
+    trace!("got friends/code regenerate request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+
+    Ok(Json(FriendCodeResponse {
+        code: controller.regenerate_code(&mayhem_id).await?,
+    }))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn redeem(
+    Path(code): Path<String>,
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendRequestResponse>, ErrorMessage> {
+    trace!("got friends/redeem request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+
+    Ok(Json(FriendRequestResponse {
+        friend_id: controller.redeem(&mayhem_id, &code).await?,
+        accepted: true,
+    }))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn request(
+    Path(friend_id): Path<String>,
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendRequestResponse>, ErrorMessage> {
+    trace!("got friends/request request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+    let accepted = controller.request(&mayhem_id, &friend_id).await?;
+
+    Ok(Json(FriendRequestResponse {
+        friend_id,
+        accepted,
+    }))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn accept(
+    Path(requester): Path<String>,
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<Json<FriendRequestResponse>, ErrorMessage> {
+    trace!("got friends/accept request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+    let accepted = controller.accept(&mayhem_id, &requester).await?;
+
+    Ok(Json(FriendRequestResponse {
+        friend_id: requester,
+        accepted,
+    }))
+}
+
+#[tracing::instrument(skip(controller))]
+async fn remove(
+    Path(friend_id): Path<String>,
+    AccessToken(access_token): AccessToken,
+    Extension(controller): Extension<FriendController>,
+) -> Result<(), ErrorMessage> {
+    trace!("got friends/remove request");
+    let mayhem_id = controller.authenticate(&access_token).await?;
+
+    if !controller.remove(&mayhem_id, &friend_id).await? {
+        return Err(FriendControllerError::NotFound.into());
+    }
+
+    Ok(())
+}
@@ -1,23 +1,25 @@
+use crate::app::controllers::mayhem::MayhemController;
 use crate::protos::data::GameplayConfigResponse;
-use crate::util::{Protobuf, Xml};
-use axum::{routing::get, Router};
-use axum_response_cache::CacheLayer;
+use crate::util::{extractors::NucleusToken, Protobuf, Xml};
+use axum::{routing::get, Extension, Router};
 use tracing::{instrument, trace};
 
 // /mh/gameplayconfig
 pub fn create_router() -> Router {
-    Router::new()
-        .route("/", get(gameplay_config))
-        .layer(CacheLayer::with_lifespan(3600))
+    // not cached, the gameplay config depends on the event of the player
+    Router::new().route("/", get(gameplay_config))
 }
 
-#[instrument]
-async fn gameplay_config() -> Result<Protobuf<GameplayConfigResponse>, Xml> {
+#[instrument(skip(token, controller))]
+async fn gameplay_config(
+    token: Option<NucleusToken>,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<Protobuf<GameplayConfigResponse>, Xml> {
     trace!("got mayhem/gameplay_config request");
-    let gameplay_config_response: GameplayConfigResponse = serde_json::from_str(
-        crate::assets::GAMEPLAY_CONFIG,
-    )
-    .map_err(Xml::internal_error().log_with_message("failed to decode GameplayConfigResponse"))?;
 
-    Ok(Protobuf(gameplay_config_response))
+    Ok(Protobuf(
+        controller
+            .gameplay_config(token.as_ref().map(|NucleusToken(token)| token))
+            .await?,
+    ))
 }
@@ -5,13 +5,16 @@ use crate::{
         com::ea::simpsons::client::{log::ClientLogMessage, metrics::ClientMetricsMessage},
         data::{
             ClientConfigResponse, CurrencyData, DeleteTokenRequest, DeleteTokenResponse,
-            ExtraLandMessage, ExtraLandResponse, GetFriendDataRequest, GetFriendDataResponse,
-            LandMessage, WholeLandTokenResponse,
+            EventsMessage, ExtraLandMessage, ExtraLandResponse, GambleResponse,
+            GetFriendDataRequest, GetFriendDataResponse, LandBackupResponseMessage, LandMessage,
+            MatchmakingResponseMessage, OffersResponseMessage, PurchaseRequestMessage,
+            PurchaseResponseMessage, WholeLandTokenResponse,
         },
+        RawLand, RawLandBackupResponse, RawMatchmakingResponse,
     },
     util::{
         extractors::{LandUpdateToken, NucleusToken},
-        Protobuf, Xml,
+        Protobuf, RawProtobuf, Xml,
     },
 };
 use axum::{
@@ -20,7 +23,6 @@ use axum::{
     routing::{get, post, put},
     Extension, Router,
 };
-use axum_response_cache::CacheLayer;
 use std::collections::HashMap;
 use tracing::{debug, instrument, trace, warn};
 
@@ -28,14 +30,11 @@ use tracing::{debug, instrument, trace, warn};
 pub fn create_router() -> Router {
     let friend_data = Router::new()
         .route("/", get(friend_data))
-        .route("/origin", get(friend_data_origin))
-        .layer(CacheLayer::with_lifespan(60));
+        .route("/origin", get(friend_data_origin));
 
     let bg_gameserver_plugin = Router::new()
-        .route(
-            "/protoClientConfig/",
-            get(client_config).layer(CacheLayer::with_lifespan(3600)),
-        )
+        // not cached, the controller only reads `client_config.toml` again once it changed
+        .route("/protoClientConfig/", get(client_config))
         .route("/protoWholeLandToken/:mayhem_id/", post(whole_land_token))
         .route(
             "/checkToken/:mayhem_id/protoWholeLandToken/",
@@ -48,12 +47,20 @@ pub fn create_router() -> Router {
         .route("/protoland/:land_id/", get(get_protoland))
         .route("/protoland/:land_id/", put(put_protoland))
         .route("/protoland/:land_id/", post(post_protoland))
+        .route("/protoland/:land_id/backups/", get(land_backups))
         .route("/protocurrency/:land_id/", get(proto_currency))
+        .route("/protopurchase/:land_id/", post(purchase))
+        .route("/gamble/:land_id/", post(gamble))
+        .route("/protooffers/:land_id/", get(offers))
+        .route("/matchmaking/:land_id/", get(matchmaking))
         .route(
             "/extraLandUpdate/:land_id/protoland/",
             post(extra_land_update),
         )
-        .route("/event/:land_id/protoland/", get(proto_event))
+        .route(
+            "/event/:land_id/protoland/",
+            get(proto_event).post(post_proto_event),
+        )
         .route("/trackinglog/", post(trackinglog))
         .route("/trackingmetrics/", post(trackingmetrics))
         .nest("/friendData", friend_data);
@@ -61,7 +68,7 @@ pub fn create_router() -> Router {
     Router::new()
         .route("/lobby/time", get(lobby_time))
         // /mh/bg_gameserver_plugin
-        .nest(&DIRECTIONS.mh_route(), bg_gameserver_plugin)
+        .nest(&DIRECTIONS.take().mh_route(), bg_gameserver_plugin)
 }
 
 #[instrument]
@@ -128,13 +135,13 @@ async fn get_protoland(
     NucleusToken(token): NucleusToken,
     LandUpdateToken(land_update_token): LandUpdateToken,
     Extension(controller): Extension<MayhemController>,
-) -> Result<Protobuf<LandMessage>, Xml> {
+) -> Result<RawProtobuf<LandMessage>, Xml> {
     trace!("got mayhem/get_protoland request");
-    Ok(Protobuf(
-        controller
-            .get_protoland(&mayhem_id, &token, &land_update_token.to_owned())
-            .await?,
-    ))
+    let RawLand { land, bytes } = controller
+        .get_protoland(&mayhem_id, &token, &land_update_token.to_owned())
+        .await?;
+
+    Ok(RawProtobuf(land, bytes))
 }
 
 #[instrument(skip(controller))]
@@ -144,10 +151,11 @@ async fn post_protoland(
     NucleusToken(token): NucleusToken,
     LandUpdateToken(land_update_token): LandUpdateToken,
     Extension(controller): Extension<MayhemController>,
-    Protobuf(land_message): Protobuf<LandMessage>,
-    // land_message: Bytes,
+    RawProtobuf(land, bytes): RawProtobuf<LandMessage>,
 ) -> Result<Xml, Xml> {
     trace!("got mayhem/post_protoland request");
+    let land_message = RawLand { land, bytes };
+
     Ok(controller
         .update_protoland(
             &mayhem_id,
@@ -171,10 +179,11 @@ async fn put_protoland(
     NucleusToken(token): NucleusToken,
     LandUpdateToken(land_update_token): LandUpdateToken,
     Extension(controller): Extension<MayhemController>,
-    Protobuf(land_message): Protobuf<LandMessage>,
-    // land_message: Bytes,
-) -> Result<Protobuf<LandMessage>, Xml> {
+    RawProtobuf(land, bytes): RawProtobuf<LandMessage>,
+) -> Result<RawProtobuf<LandMessage>, Xml> {
     trace!("got mayhem/put_protoland request");
+    let land_message = RawLand { land, bytes };
+
     Ok(controller
         .update_protoland(
             &mayhem_id,
@@ -184,7 +193,27 @@ async fn put_protoland(
             false,
         )
         .await
-        .map(|_| Protobuf(land_message))?)
+        .map(|_| RawProtobuf(land_message.land, land_message.bytes))?)
+}
+
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/protoland/:land_id/backups/
+async fn land_backups(
+    Path(mayhem_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<RawProtobuf<LandBackupResponseMessage>, Xml> {
+    trace!("got mayhem/land_backups request");
+    let page = query
+        .get("page")
+        .and_then(|page| page.parse().ok())
+        .unwrap_or_default();
+
+    let RawLandBackupResponse { response, bytes } =
+        controller.land_backups(&mayhem_id, &token, page).await?;
+
+    Ok(RawProtobuf(response, bytes))
 }
 
 #[instrument(skip(controller))]
@@ -200,6 +229,56 @@ async fn proto_currency(
     ))
 }
 
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/protopurchase/:land_id/
+async fn purchase(
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+    Protobuf(request): Protobuf<PurchaseRequestMessage>,
+) -> Result<Protobuf<PurchaseResponseMessage>, Xml> {
+    trace!("got mayhem/purchase request");
+    Ok(Protobuf(
+        controller.purchase(&mayhem_id, &token, &request).await?,
+    ))
+}
+
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/protooffers/:land_id/
+async fn offers(
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<Protobuf<OffersResponseMessage>, Xml> {
+    trace!("got mayhem/offers request");
+    Ok(Protobuf(controller.offers(&mayhem_id, &token).await?))
+}
+
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/matchmaking/:land_id/
+async fn matchmaking(
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<RawProtobuf<MatchmakingResponseMessage>, Xml> {
+    trace!("got mayhem/matchmaking request");
+    let RawMatchmakingResponse { response, bytes } =
+        controller.matchmaking(&mayhem_id, &token).await?;
+
+    Ok(RawProtobuf(response, bytes))
+}
+
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/gamble/:land_id/
+async fn gamble(
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<Protobuf<GambleResponse>, Xml> {
+    trace!("got mayhem/gamble request");
+    Ok(Protobuf(controller.gamble(&mayhem_id, &token).await?))
+}
+
 #[instrument(skip(controller))]
 async fn extra_land_update(
     headers: HeaderMap,
@@ -223,14 +302,32 @@ async fn extra_land_update(
     ))
 }
 
-#[instrument]
+#[instrument(skip(controller, events))]
+// /mh/bg_gameserver_plugin/event/:land_id/protoland/
 async fn proto_event(
-    headers: HeaderMap,
-    Protobuf(event): Protobuf<crate::protos::data::EventsMessage>,
-) -> Result<Protobuf<()>, Xml> {
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+    Protobuf(events): Protobuf<EventsMessage>,
+) -> Result<Protobuf<EventsMessage>, Xml> {
     trace!("got mayhem/proto_event request");
+    Ok(Protobuf(
+        controller.poll_events(&mayhem_id, &token, &events).await?,
+    ))
+}
 
-    Ok(Protobuf(()))
+#[instrument(skip(controller, events))]
+// /mh/bg_gameserver_plugin/event/:land_id/protoland/
+async fn post_proto_event(
+    Path(mayhem_id): Path<String>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+    Protobuf(events): Protobuf<EventsMessage>,
+) -> Result<Protobuf<EventsMessage>, Xml> {
+    trace!("got mayhem/post_proto_event request");
+    Ok(Protobuf(
+        controller.push_events(&mayhem_id, &token, &events).await?,
+    ))
 }
 
 #[instrument(skip(log_message))]
@@ -264,10 +361,16 @@ async fn trackingmetrics(
     ))
 }
 
-#[instrument]
-async fn lobby_time() -> Result<Xml, Xml> {
+#[instrument(skip(token, controller))]
+async fn lobby_time(
+    token: Option<NucleusToken>,
+    Extension(controller): Extension<MayhemController>,
+) -> Result<Xml, Xml> {
     trace!("got mayhem/lobby_time request");
-    let epoch = MayhemController::get_lobby_time().map_err(|_| Xml::internal_error())?;
+    let epoch = controller
+        .lobby_time(token.as_ref().map(|NucleusToken(token)| token))
+        .await
+        .map_err(|_| Xml::internal_error())?;
 
     Ok(Xml::ok(format!(
         r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
@@ -276,28 +379,24 @@ async fn lobby_time() -> Result<Xml, Xml> {
     )))
 }
 
-#[instrument]
-// TODO implement friend data
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/friendData/
 async fn friend_data(
-    Protobuf(_req): Protobuf<GetFriendDataRequest>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+    Protobuf(request): Protobuf<GetFriendDataRequest>,
 ) -> Result<Protobuf<GetFriendDataResponse>, Xml> {
     trace!("got mayhem/friend_data request");
-
-    Ok(Protobuf(GetFriendDataResponse {
-        friend_data: vec![],
-        error: None,
-    }))
+    Ok(Protobuf(controller.friend_data(&token, &request).await?))
 }
 
-#[instrument]
-// TODO implement friend data
+#[instrument(skip(controller))]
+// /mh/bg_gameserver_plugin/friendData/origin
 async fn friend_data_origin(
-    Protobuf(_req): Protobuf<GetFriendDataRequest>,
+    NucleusToken(token): NucleusToken,
+    Extension(controller): Extension<MayhemController>,
+    Protobuf(request): Protobuf<GetFriendDataRequest>,
 ) -> Result<Protobuf<GetFriendDataResponse>, Xml> {
     trace!("got mayhem/friend_data_origin request");
-
-    Ok(Protobuf(GetFriendDataResponse {
-        friend_data: vec![],
-        error: None,
-    }))
+    Ok(Protobuf(controller.friend_data(&token, &request).await?))
 }
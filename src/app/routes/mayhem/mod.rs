@@ -1,4 +1,14 @@
-use axum::Router;
+use crate::app::{
+    controllers::client_version::ClientVersionController, models::dashboard::VersionStatus,
+};
+use crate::xml_response;
+use axum::{
+    extract::Request,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use tracing::instrument;
 
 pub mod config;
 pub mod games;
@@ -16,4 +26,23 @@ pub fn create_router() -> Router {
         .nest("/userstats", userstats::create_router())
         .nest("/link", link::create_router())
         .nest("/clienttelemetry", telemetry::create_router())
+        .layer(middleware::from_fn(client_version))
+}
+
+/// rejects clients which are older than the version policy allows
+#[instrument(skip_all)]
+async fn client_version(request: Request, next: Next) -> Response {
+    let headers = request.headers();
+
+    if let Some(version) = ClientVersionController::version(headers) {
+        let platform = ClientVersionController::platform(headers);
+
+        if ClientVersionController::default().check(platform.as_deref(), &version)
+            == VersionStatus::Rejected
+        {
+            return xml_response!(400, "PROTOCOL_MISMATCH", "client_version").into_response();
+        }
+    }
+
+    next.run(request).await
 }
@@ -1,6 +1,7 @@
 mod connect;
 mod dashboard;
 mod director;
+mod friends;
 mod mayhem;
 mod proxy;
 mod tracking;
@@ -23,6 +24,7 @@ pub fn create_router() -> Router {
     let proxy_router = proxy::create_router();
     let connect_router = connect::create_router();
     let dashboard_router = dashboard::create_router();
+    let friends_router = friends::create_router();
     let dlc_service = game_assets();
 
     let mut service = Router::new()
@@ -37,6 +39,7 @@ pub fn create_router() -> Router {
     .nest("//proxy", proxy_router) /* //proxy/identity/geoagerequirements?client_id=simpsons4-android-client  */
     .nest("/connect", connect_router)
     .nest("/dashboard", dashboard_router)
+    .nest("/friends", friends_router)
     .layer(with_tracing!());
 
     let options = OPTIONS.take();
@@ -20,10 +20,59 @@ default_donuts_empty = {default_donuts_header}{" "}{-error_invalid_number}
 
 config_header = Server Konfiguration
 config_save = Einstellungen Speichern
+config_reload = Konfigurationsdateien neu laden
+config_reload_unchanged = Keine Änderungen
+config_reload_applied = Übernommen:
+config_reload_restart = Neustart des Servers nötig für:
 
 events_header = Event Konfiguration
 events_current_status = Aktuelles Event:{" "}
 
+rotation_header = Event-Rotation
+rotation_enabled = Events automatisch wechseln
+rotation_interval = Tage pro Event
+rotation_save = Übernehmen
+rotation_next = Nächstes Event
+rotation_upcoming = Kommende Events
+rotation_playlist = Playlist
+rotation_remove = Entfernen
+rotation_select = Event auswählen
+rotation_add = Hinzufügen
+
+catalog_header = Event-Katalog
+catalog_start = Start-Zeitstempel
+catalog_name = Name
+catalog_duration = Dauer in Tagen
+catalog_packages = DLC-Pakete, durch Komma getrennt
+catalog_days = Tage
+catalog_save = Event speichern
+catalog_edit = Bearbeiten
+catalog_remove = Entfernen
+
+client_config_header = Client-Konfiguration
+gameplay_config_header = Gameplay-Konfiguration
+gameplay_config_global = Alle Events
+config_item_name = Name des Eintrags
+config_item_value = Wert
+config_item_add = Hinzufügen
+config_item_save = Speichern
+config_item_reset = Zurücksetzen
+config_item_remove = Entfernen
+
+client_versions_header = Client-Versionen
+client_versions_platform = Plattform
+client_versions_minimum = Mindestens
+client_versions_current = Aktuell
+client_versions_allowed = Ebenfalls erlaubt
+client_versions_default = Andere Plattformen
+client_versions_add = Plattform hinzufügen
+client_versions_save = Richtlinie speichern
+client_versions_remove = Entfernen
+client_versions_seen = Verbundene Versionen
+client_versions_allowed_status = Erlaubt
+client_versions_upgrade_status = Update verfügbar
+client_versions_rejected_status = Abgelehnt
+
 status_header = Server Status
 status_online = Online
 status_offline = Offline
@@ -32,13 +81,54 @@ health_header = Server Verbindung:{" "}
 health_uptime = Laufzeit:{" "}
 
 lobby_status = Lobbyzeit:{" "}
+lobby_clock_header = Lobbyuhr
+lobby_clock_frozen = Uhr anhalten
+lobby_clock_offset = Versatz in Sekunden
+lobby_clock_save = Übernehmen
 
 players_status = Spieler:{" "}
 
+players_header = Spieler
+players_mayhem_id = Mayhem ID
+players_name = Name
+players_matchmaking = Nachbarsuche
+players_select = Verwalten
+
+backups_header = Stadt-Backups von
+backups_empty = Noch keine Stadt-Backups gespeichert.
+backups_restore = Wiederherstellen
+backups_restored = Stadt-Backup wiederhergestellt vom
+
+currency_header = Donut-Verlauf von
+currency_empty = Noch keine Donut-Buchungen erfasst.
+currency_date = Datum
+currency_source = Quelle
+currency_amount = Betrag
+currency_balance = Kontostand
+currency_newer = Neuer
+currency_older = Älter
+
+friends_header = Freunde von
+friends_code = Freundescode
+friends_regenerate = Neu erzeugen
+friends_empty = Noch keine Freunde.
+friends_requests = Offene Anfragen
+friends_accept = Annehmen
+friends_decline = Ablehnen
+friends_remove = Entfernen
+friends_redeem = Einlösen
+friends_redeem_placeholder = Freundescode eines anderen Spielers
+
+override_header = Event-Überschreibung von
+override_enabled = Spieler in einem eigenen Event halten
+override_save = Speichern
+override_saved = Event-Überschreibung gespeichert.
+
 logs_save = Logdatei Speichern
 
 route_credits = Credits
 route_home = Home
 route_logs = Logs
+route_players = Spieler
 route_login = Login
 route_logout = Logout
@@ -20,10 +20,59 @@ default_donuts_empty = {default_donuts_header}{" "}{-error_invalid_number}
 
 config_header = Server Configuration
 config_save = Save settings
+config_reload = Reload config files
+config_reload_unchanged = Nothing changed
+config_reload_applied = Applied:
+config_reload_restart = Restart the server to apply:
 
 events_header = Event Configuration
 events_current_status = Current Event:{" "}
 
+rotation_header = Event Rotation
+rotation_enabled = Switch events automatically
+rotation_interval = Days per event
+rotation_save = Apply
+rotation_next = Next event
+rotation_upcoming = Upcoming Events
+rotation_playlist = Playlist
+rotation_remove = Remove
+rotation_select = Select an event
+rotation_add = Add
+
+catalog_header = Event Catalog
+catalog_start = Start timestamp
+catalog_name = Name
+catalog_duration = Duration in days
+catalog_packages = DLC packages, comma separated
+catalog_days = days
+catalog_save = Save event
+catalog_edit = Edit
+catalog_remove = Remove
+
+client_config_header = Client Config
+gameplay_config_header = Gameplay Config
+gameplay_config_global = All events
+config_item_name = Item name
+config_item_value = Value
+config_item_add = Add
+config_item_save = Save
+config_item_reset = Reset
+config_item_remove = Remove
+
+client_versions_header = Client Versions
+client_versions_platform = Platform
+client_versions_minimum = Minimum
+client_versions_current = Current
+client_versions_allowed = Also allowed
+client_versions_default = Other platforms
+client_versions_add = Add platform
+client_versions_save = Save policy
+client_versions_remove = Remove
+client_versions_seen = Connected Versions
+client_versions_allowed_status = Allowed
+client_versions_upgrade_status = Upgrade available
+client_versions_rejected_status = Rejected
+
 status_header = Server Status
 status_online = Online
 status_offline = Offline
@@ -32,13 +81,54 @@ health_header = Server Health:{" "}
 health_uptime = Uptime:{" "}
 
 lobby_status = Lobby Time:{" "}
+lobby_clock_header = Lobby Clock
+lobby_clock_frozen = Freeze clock
+lobby_clock_offset = Offset in seconds
+lobby_clock_save = Apply
 
 players_status = Players:{" "}
 
+players_header = Players
+players_mayhem_id = Mayhem ID
+players_name = Name
+players_matchmaking = Matchmaking
+players_select = Manage
+
+backups_header = Land Backups of
+backups_empty = No land backups stored yet.
+backups_restore = Restore
+backups_restored = Restored land backup from
+
+currency_header = Donut History of
+currency_empty = No donut transactions recorded yet.
+currency_date = Date
+currency_source = Source
+currency_amount = Amount
+currency_balance = Balance
+currency_newer = Newer
+currency_older = Older
+
+friends_header = Friends of
+friends_code = Friend Code
+friends_regenerate = Regenerate
+friends_empty = No friends yet.
+friends_requests = Pending Requests
+friends_accept = Accept
+friends_decline = Decline
+friends_remove = Remove
+friends_redeem = Redeem
+friends_redeem_placeholder = Friend code of another player
+
+override_header = Event Override of
+override_enabled = Keep this player in its own event
+override_save = Save
+override_saved = Event override saved.
+
 logs_save = Save Logs
 
 route_credits = Credits
 route_home = Home
 route_logs = Logs
+route_players = Players
 route_login = Login
 route_logout = Logout
@@ -2,13 +2,18 @@ use crate::app::models::direction::Direction;
 use server::ServerOptions;
 use std::sync::Mutex;
 
+pub use direction::server_url;
+pub use reload::{reload, watch};
+pub use server::LobbyClock;
+
 mod args;
 mod direction;
 mod env;
+mod reload;
 mod server;
 
 lazy_static::lazy_static! {
-    pub static ref DIRECTIONS: Direction = Direction::new();
+    pub static ref DIRECTIONS: Mutex<Direction> = Mutex::new(Direction::new());
     pub static ref OPTIONS: Mutex<ServerOptions> = Mutex::new(ServerOptions::new());
 }
 
@@ -22,3 +27,12 @@ impl OPTIONS {
         self.lock().unwrap()
     }
 }
+
+impl DIRECTIONS {
+    /// locks and unwraps the mutex
+    ///
+    /// panics if mutex fails to lock
+    pub fn take(&self) -> std::sync::MutexGuard<'_, Direction> {
+        self.lock().unwrap()
+    }
+}
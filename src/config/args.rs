@@ -1,3 +1,4 @@
+use crate::storage::StorageBackend;
 use clap::Parser;
 
 #[derive(Debug, Parser)]
@@ -25,4 +26,11 @@ pub struct Args {
         long_help = "Enables logging of assets. This can be very noisy when new clients download dlcs and is disabled by default"
     )]
     pub log_assets: Option<bool>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Migrate saves into the configured storage and exit",
+        long_help = "Copies all land and currency saves from the given storage backend into the storage backend configured in server.toml and exits afterwards"
+    )]
+    pub migrate_storage: Option<StorageBackend>,
 }
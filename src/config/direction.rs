@@ -1,9 +1,9 @@
-use super::OPTIONS;
+use super::{server::ServerOptions, OPTIONS};
 use crate::{
-    app::models::direction::{Direction, DirectionToml, HashMapToVec, KVPair},
-    util::{relative_path, DIRECTORIES},
+    app::models::direction::{Direction, DirectionProfile, DirectionToml, HashMapToVec, KVPair},
+    util::config_path_for,
 };
-use std::{env, fs::create_dir_all};
+use std::{env, fs::create_dir_all, path::PathBuf};
 
 impl Direction {
     pub fn new() -> Self {
@@ -14,13 +14,13 @@ impl Direction {
     }
 }
 
+/// path of `directions.toml`
+pub fn path(portable: bool) -> anyhow::Result<PathBuf> {
+    Ok(config_path_for(portable, "directions.toml")?)
+}
+
 fn read_toml() -> anyhow::Result<DirectionToml> {
-    let mut path = if OPTIONS.take().portable {
-        relative_path().map_err(anyhow::Error::from)?
-    } else {
-        DIRECTORIES.config_local_dir().to_path_buf()
-    };
-    path.push("directions.toml");
+    let path = path(OPTIONS.take().portable)?;
 
     if !path.exists() {
         let parent = path.parent().expect("path is valid utf-8");
@@ -40,8 +40,18 @@ fn read_toml() -> anyhow::Result<DirectionToml> {
 }
 
 pub fn read_config() -> anyhow::Result<Direction> {
+    let options = OPTIONS.take().clone();
+
+    build(read_toml().unwrap_or_default(), &options)
+}
+
+/// reads `directions.toml` again for the server config `options`, failing if the file is invalid
+pub fn reread_config(options: &ServerOptions) -> anyhow::Result<Direction> {
+    build(read_toml()?, options)
+}
+
+fn build(overrides: DirectionToml, options: &ServerOptions) -> anyhow::Result<Direction> {
     let base: Direction = serde_json::from_str(crate::assets::DIRECTION_ROW_CONFIG)?;
-    let overrides = read_toml().unwrap_or_default();
 
     Ok(Direction {
         client_secret: overrides.client_secret.unwrap_or(base.client_secret),
@@ -57,22 +67,45 @@ pub fn read_config() -> anyhow::Result<Direction> {
             .map(<Vec<KVPair> as HashMapToVec<_, _, _>>::map)
             .unwrap_or(base.server_data)
             .into_iter()
-            .map(rewrite_server_address)
+            .map(|kv| rewrite_server_address(kv, options))
+            .collect(),
+        profiles: overrides
+            .profiles
+            .into_iter()
+            .map(|profile| DirectionProfile {
+                platform: profile.platform,
+                package_id: profile.package_id,
+                client_id: profile.client_id,
+                mdm_app_key: profile.mdm_app_key,
+                disabled_features: profile.disabled_features,
+                poll_intervals: profile
+                    .poll_intervals
+                    .map(<Vec<KVPair> as HashMapToVec<_, _, _>>::map),
+                server_data: profile.server_data.map(|server_data| {
+                    <Vec<KVPair> as HashMapToVec<_, _, _>>::map(server_data)
+                        .into_iter()
+                        .map(|kv| rewrite_server_address(kv, options))
+                        .collect()
+                }),
+            })
             .collect(),
         ..base
     })
 }
 
-fn rewrite_server_address(mut kv: KVPair) -> KVPair {
-    let config = OPTIONS.take().clone();
+/// base url of the server as configured in `server.toml`
+pub fn server_url(config: &ServerOptions) -> String {
+    match config.port {
+        80 | 443 => format!("{}/", config.server_address.clone()),
+        _ => {
+            format!("{}:{}/", config.server_address, config.port)
+        }
+    }
+}
 
+fn rewrite_server_address(mut kv: KVPair, config: &ServerOptions) -> KVPair {
     if kv.value.is_empty() {
-        kv.value = match config.port {
-            80 | 443 => format!("{}/", config.server_address.clone()),
-            _ => {
-                format!("{}:{}/", config.server_address, config.port)
-            }
-        };
+        kv.value = server_url(config);
     }
 
     kv
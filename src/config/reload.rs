@@ -0,0 +1,148 @@
+use super::{direction, server::ServerOptions, DIRECTIONS, OPTIONS};
+use crate::app::models::{dashboard::ConfigReloadResponse, direction::Direction};
+use std::{
+    path::PathBuf,
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
+use tracing::{error, info, warn};
+
+/// settings of `server.toml` which are only read at startup, see `ServerOptions::keep_restart_settings`
+const SERVER_RESTART_REQUIRED: &[&str] = &[
+    "port",
+    "database",
+    "storage",
+    "dlc_folder",
+    "dlc_routes",
+    "log_assets",
+];
+/// settings of `directions.toml` which are only read at startup
+const DIRECTION_RESTART_REQUIRED: &[&str] = &["mayhemGameCode"];
+
+/// config the server started with, taken before the first reload
+static STARTUP: OnceLock<(ServerOptions, Direction)> = OnceLock::new();
+
+/// reads `server.toml` and `directions.toml` again and swaps the live config if both are valid.
+///
+/// returns the changed settings, split by whether they apply right away or after a restart
+pub fn reload() -> anyhow::Result<ConfigReloadResponse> {
+    let current_options = OPTIONS.take().clone();
+    let current_direction = DIRECTIONS.take().clone();
+    let (startup_options, startup_direction) =
+        STARTUP.get_or_init(|| (current_options.clone(), current_direction.clone()));
+
+    let mut options = current_options.reread()?;
+
+    let mut response = ConfigReloadResponse::default();
+    changes(
+        "server.toml",
+        (startup_options, &current_options, &options),
+        SERVER_RESTART_REQUIRED,
+        &mut response,
+    )?;
+    // e.g. a changed port must not be advertised before the server listens on it
+    options.keep_restart_settings(startup_options);
+
+    let mut direction = direction::reread_config(&options)?;
+    changes(
+        "directions.toml",
+        (startup_direction, &current_direction, &direction),
+        DIRECTION_RESTART_REQUIRED,
+        &mut response,
+    )?;
+    direction.mayhem_game_code = startup_direction.mayhem_game_code.clone();
+    // profiles are not part of the served directions, so they are compared on their own
+    if serde_json::to_value(&current_direction.profiles)?
+        != serde_json::to_value(&direction.profiles)?
+    {
+        response
+            .applied
+            .push("directions.toml: profiles".to_owned());
+    }
+
+    {
+        // options are always locked before directions
+        let mut live_options = OPTIONS.take();
+        let mut live_direction = DIRECTIONS.take();
+        *live_options = options;
+        *live_direction = direction;
+    }
+
+    for setting in &response.applied {
+        info!("applied {setting}");
+    }
+    for setting in &response.restart_required {
+        warn!("{setting} changed, restart the server to apply it");
+    }
+
+    Ok(response)
+}
+
+/// reloads the config whenever `server.toml` or `directions.toml` changed
+pub async fn watch() {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+    // the dashboard changes the live config as well, so remember the startup config right away
+    STARTUP.get_or_init(|| (OPTIONS.take().clone(), DIRECTIONS.take().clone()));
+    let mut last_modified = modified();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let modified = modified();
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        if let Err(e) = reload() {
+            error!("failed to reload config, keeping the current one: {e}");
+        }
+    }
+}
+
+/// adds the top level settings which differ between `current` and `new` to `response`.
+///
+/// settings in `restart_required` are compared with the config the server started with instead
+fn changes<T: serde::Serialize>(
+    file: &str,
+    (startup, current, new): (&T, &T, &T),
+    restart_required: &[&str],
+    response: &mut ConfigReloadResponse,
+) -> anyhow::Result<()> {
+    let (serde_json::Value::Object(startup), serde_json::Value::Object(current)) = (
+        serde_json::to_value(startup)?,
+        serde_json::to_value(current)?,
+    ) else {
+        return Ok(());
+    };
+    let serde_json::Value::Object(new) = serde_json::to_value(new)? else {
+        return Ok(());
+    };
+
+    for (key, value) in new {
+        if restart_required.contains(&key.as_str()) {
+            if startup.get(&key) != Some(&value) {
+                response.restart_required.push(format!("{file}: {key}"));
+            }
+        } else if current.get(&key) != Some(&value) {
+            response.applied.push(format!("{file}: {key}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn modified() -> [Option<SystemTime>; 2] {
+    let modified = |path: anyhow::Result<PathBuf>| {
+        path.ok()
+            .and_then(|path| path.metadata().ok())
+            .and_then(|meta| meta.modified().ok())
+    };
+    let portable = OPTIONS.take().portable;
+
+    [
+        modified(ServerOptions::path(portable)),
+        modified(direction::path(portable)),
+    ]
+}
@@ -1,10 +1,16 @@
 use super::args::Args;
 use super::env::EnvOptions;
-use crate::util::{relative_path, DIRECTORIES};
+use crate::{
+    app::models::dashboard::ClientVersionPolicy,
+    storage::StorageBackend,
+    util::{config_path_for, millis_from_unix_epoch, relative_path, DIRECTORIES, UPTIME},
+};
 use clap::Parser;
 use std::{
+    collections::BTreeMap,
     fs::create_dir_all,
     path::{Path, PathBuf},
+    time::{SystemTimeError, UNIX_EPOCH},
 };
 use tracing::{debug, info};
 
@@ -18,9 +24,45 @@ pub struct ServerOptions {
     pub dlc_routes: Vec<String>,
     pub database: String,
     pub server_address: String,
+    /// hosts the server may be reached at besides `server_address`, e.g. `192.168.1.10:8080`.
+    /// directions advertise the host of the request if it is listed here
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
     pub log_assets: bool,
+    /// amount of land revisions kept per user. `0` keeps all revisions
+    #[serde(default = "default_land_revisions")]
+    pub land_revisions: u32,
+    /// max age of land revisions in seconds. `0` keeps revisions forever
+    #[serde(default = "default_land_revisions_max_age")]
+    pub land_revisions_max_age: u64,
+    /// backend used to persist land and currency saves
+    #[serde(default)]
+    pub storage: StorageBackend,
+    /// donuts granted per store sku
+    #[serde(default)]
+    pub donut_packs: BTreeMap<String, u32>,
+    /// matchmaking prefers lands within this many levels of the requesting player. `0` picks any land
+    #[serde(default = "default_matchmaking_level_range")]
+    pub matchmaking_level_range: u32,
+    /// cost and prizes of the mystery box
+    #[serde(default)]
+    pub gamble: GambleOptions,
+    /// start timestamp of the selected event, see the event catalog. `0` is normal play
+    #[serde(default)]
+    pub current_event: u64,
+    /// virtual clock reported to the game
+    #[serde(default)]
+    pub lobby_clock: LobbyClock,
+    /// switches through the events of the playlist automatically
+    #[serde(default)]
+    pub event_rotation: EventRotation,
+    /// client builds which may connect
+    #[serde(default)]
+    pub client_versions: ClientVersionPolicy,
     #[serde(skip)]
     pub portable: bool,
+    #[serde(skip)]
+    pub migrate_storage: Option<StorageBackend>,
 }
 
 impl ServerOptions {
@@ -28,6 +70,7 @@ impl ServerOptions {
         let env = EnvOptions::parse().expect("valid arguments given");
         let args = Args::parse();
         let options = Self::read(&args).expect("reading/writing config succeeds");
+        let migrate_storage = args.migrate_storage;
 
         if args.portable {
             info!(
@@ -35,7 +78,39 @@ impl ServerOptions {
             )
         }
 
-        env.merge(options, args)
+        ServerOptions {
+            migrate_storage,
+            ..env.merge(options, args)
+        }
+    }
+
+    /// reads `server.toml` again, keeping the overrides of env and cli arguments.
+    ///
+    /// fails if the file is missing or invalid
+    pub fn reread(&self) -> anyhow::Result<ServerOptions> {
+        let env = EnvOptions::parse()?;
+        let args = Args::try_parse()?;
+        let content = std::fs::read_to_string(Self::path(args.portable)?)?;
+        let options = Self::parse(&content, args.portable)?;
+
+        let options = ServerOptions {
+            migrate_storage: self.migrate_storage,
+            ..env.merge(options, args)
+        };
+        options.validate()?;
+
+        Ok(options)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.port != 0, "port must not be 0");
+        anyhow::ensure!(
+            !self.server_address.is_empty(),
+            "server_address must not be empty"
+        );
+        anyhow::ensure!(!self.dlc_folder.is_empty(), "dlc_folder must not be empty");
+
+        Ok(())
     }
 
     pub fn dlc_folder(&self) -> PathBuf {
@@ -49,7 +124,25 @@ impl ServerOptions {
         path
     }
 
+    /// current time of the lobby clock in milliseconds
+    pub fn lobby_time(&self) -> Result<u128, SystemTimeError> {
+        self.lobby_clock.now(self.current_event)
+    }
+
+    /// writes the config to `path`.
+    ///
+    /// settings which only apply after a restart keep the value of the existing file,
+    /// the live config holds their startup values
     pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.save_with(path, |_| {})
+    }
+
+    /// like `save`, but `update` may change the settings which only apply after a restart
+    pub fn save_with(
+        &self,
+        path: impl AsRef<Path>,
+        update: impl FnOnce(&mut ServerOptions),
+    ) -> anyhow::Result<()> {
         let path = path.as_ref();
         let parent = path.parent().expect("parent path is valid utf-8");
 
@@ -57,30 +150,32 @@ impl ServerOptions {
             create_dir_all(parent)?;
         }
 
-        Ok(std::fs::write(path, toml::to_string_pretty(self)?)?)
+        let mut options = self.clone();
+        if let Ok(content) = std::fs::read_to_string(path) {
+            options.keep_restart_settings(&Self::parse(&content, self.portable)?);
+        }
+        update(&mut options);
+
+        Ok(std::fs::write(path, toml::to_string_pretty(&options)?)?)
+    }
+
+    /// copies the settings which are only read at startup from `other`
+    pub(crate) fn keep_restart_settings(&mut self, other: &ServerOptions) {
+        self.port = other.port;
+        self.database = other.database.clone();
+        self.storage = other.storage;
+        self.dlc_folder = other.dlc_folder.clone();
+        self.dlc_routes = other.dlc_routes.clone();
+        self.log_assets = other.log_assets;
     }
 
     /// reads the server config from either relative (if portable mode) or config path and parses it.
     fn read(args: &Args) -> anyhow::Result<ServerOptions> {
-        let mut path = if args.portable {
-            relative_path()?
-        } else {
-            DIRECTORIES.config_local_dir().to_path_buf()
-        };
-        path.push("server.toml");
+        let path = Self::path(args.portable)?;
 
         if let Ok(content) = std::fs::read_to_string(&path) {
             debug!("local config exists");
-            Ok(toml::from_str::<ServerOptions>(&content).map(|mut opts| {
-                if let Some(address) = opts.server_address.strip_suffix("/") {
-                    // strip trailing slash
-                    opts.server_address = address.to_owned();
-                }
-
-                opts.portable = args.portable;
-
-                opts
-            })?)
+            Self::parse(&content, args.portable)
         } else {
             debug!("local config does not exist. default creating config");
             let server_options = ServerOptions {
@@ -93,6 +188,24 @@ impl ServerOptions {
             Ok(server_options)
         }
     }
+
+    /// path of `server.toml`
+    pub fn path(portable: bool) -> anyhow::Result<PathBuf> {
+        Ok(config_path_for(portable, "server.toml")?)
+    }
+
+    fn parse(content: &str, portable: bool) -> anyhow::Result<ServerOptions> {
+        Ok(toml::from_str::<ServerOptions>(content).map(|mut opts| {
+            if let Some(address) = opts.server_address.strip_suffix("/") {
+                // strip trailing slash
+                opts.server_address = address.to_owned();
+            }
+
+            opts.portable = portable;
+
+            opts
+        })?)
+    }
 }
 
 impl Default for ServerOptions {
@@ -106,8 +219,316 @@ impl Default for ServerOptions {
             dlc_routes: vec!["/gameassets".to_owned()],
             database: "server.db".to_owned(),
             server_address: "http://127.0.0.1".to_owned(),
+            allowed_hosts: vec![],
             log_assets: cfg!(debug_assertions),
+            land_revisions: default_land_revisions(),
+            land_revisions_max_age: default_land_revisions_max_age(),
+            storage: StorageBackend::default(),
+            donut_packs: BTreeMap::new(),
+            matchmaking_level_range: default_matchmaking_level_range(),
+            gamble: GambleOptions::default(),
+            current_event: 0,
+            lobby_clock: LobbyClock::default(),
+            event_rotation: EventRotation::default(),
+            client_versions: ClientVersionPolicy::default(),
             portable: false,
+            migrate_storage: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GambleOptions {
+    /// donuts deducted for each gamble
+    pub cost: u32,
+    /// prizes drawn with a chance proportional to their weight
+    pub prizes: Vec<GamblePrize>,
+}
+
+impl Default for GambleOptions {
+    fn default() -> Self {
+        Self {
+            cost: 5,
+            prizes: vec![
+                GamblePrize {
+                    donuts: 0,
+                    weight: 50,
+                },
+                GamblePrize {
+                    donuts: 5,
+                    weight: 30,
+                },
+                GamblePrize {
+                    donuts: 10,
+                    weight: 15,
+                },
+                GamblePrize {
+                    donuts: 50,
+                    weight: 5,
+                },
+            ],
+        }
+    }
+}
+
+impl GambleOptions {
+    /// draws a random prize, `None` if there is nothing to draw from
+    pub fn draw(&self) -> Option<u32> {
+        let total = self
+            .prizes
+            .iter()
+            .map(|prize| prize.weight as u64)
+            .sum::<u64>();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rand::random_range(0..total);
+
+        self.prizes.iter().find_map(|prize| {
+            if roll < prize.weight as u64 {
+                Some(prize.donuts)
+            } else {
+                roll -= prize.weight as u64;
+                None
+            }
+        })
+    }
+}
+
+/// starts at the selected event and advances in real time from the moment it got selected.
+/// normal play follows the wall clock
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct LobbyClock {
+    /// unix timestamp in milliseconds the current event got selected at. `0` counts from the server start
+    #[serde(default)]
+    pub selected_at: u64,
+    /// unix timestamp in milliseconds the clock got frozen at
+    #[serde(default)]
+    pub frozen_at: Option<u64>,
+    /// milliseconds the clock was frozen since the event got selected
+    #[serde(default)]
+    pub paused: u64,
+    /// seconds added to the clock, may be negative
+    #[serde(default)]
+    pub offset: i64,
+}
+
+impl LobbyClock {
+    /// current time of the clock in milliseconds for the event starting at `event`
+    pub fn now(&self, event: u64) -> Result<u128, SystemTimeError> {
+        let selected_at = match self.selected_at {
+            0 => UPTIME.duration_since(UNIX_EPOCH)?.as_millis(),
+            selected_at => selected_at as u128,
+        };
+        let now = self
+            .frozen_at
+            .map_or_else(millis_from_unix_epoch, |frozen_at| Ok(frozen_at as u128))?;
+        let start = match event {
+            0 => selected_at,
+            event => event as u128 * 1000,
+        };
+
+        let elapsed = now
+            .saturating_sub(selected_at)
+            .saturating_sub(self.paused as u128);
+
+        Ok((start + elapsed).saturating_add_signed(self.offset as i128 * 1000))
+    }
+
+    /// restarts the clock at a newly selected event
+    pub fn select(&mut self) -> Result<(), SystemTimeError> {
+        let now = millis_from_unix_epoch()? as u64;
+
+        self.selected_at = now;
+        self.paused = 0;
+        if self.frozen_at.is_some() {
+            self.frozen_at = Some(now);
+        }
+
+        Ok(())
+    }
+
+    /// stops or resumes the clock, a resumed clock continues at the time it got frozen at
+    pub fn freeze(&mut self, frozen: bool) -> Result<(), SystemTimeError> {
+        let now = millis_from_unix_epoch()? as u64;
+
+        match (frozen, self.frozen_at) {
+            (true, None) => {
+                // pin the start, so the frozen time survives restarts
+                if self.selected_at == 0 {
+                    self.selected_at = UPTIME.duration_since(UNIX_EPOCH)?.as_millis() as u64;
+                }
+                self.frozen_at = Some(now);
+            }
+            (false, Some(frozen_at)) => {
+                self.paused += now.saturating_sub(frozen_at);
+                self.frozen_at = None;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EventRotation {
+    #[serde(default)]
+    pub enabled: bool,
+    /// seconds an event stays selected unless its playlist entry says otherwise
+    #[serde(default = "default_rotation_interval")]
+    pub interval: u64,
+    /// unix timestamp in seconds the next playlist entry gets selected at
+    #[serde(default)]
+    pub next_switch: u64,
+}
+
+impl Default for EventRotation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: default_rotation_interval(),
+            next_switch: 0,
         }
     }
 }
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct GamblePrize {
+    pub donuts: u32,
+    pub weight: u32,
+}
+
+fn default_rotation_interval() -> u64 {
+    // 14 days
+    60 * 60 * 24 * 14
+}
+
+fn default_matchmaking_level_range() -> u32 {
+    5
+}
+
+fn default_land_revisions() -> u32 {
+    20
+}
+
+fn default_land_revisions_max_age() -> u64 {
+    // 30 days
+    60 * 60 * 24 * 30
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LobbyClock;
+
+    const EVENT: u64 = 1349265600;
+
+    /// clock selected at 1000s which got frozen 60s later
+    fn frozen() -> LobbyClock {
+        LobbyClock {
+            selected_at: 1_000_000,
+            frozen_at: Some(1_060_000),
+            paused: 0,
+            offset: 0,
+        }
+    }
+
+    #[test]
+    fn now_starts_at_the_event() {
+        assert_eq!(frozen().now(EVENT).unwrap(), EVENT as u128 * 1000 + 60_000);
+    }
+
+    #[test]
+    fn now_follows_the_wall_clock_in_normal_play() {
+        assert_eq!(frozen().now(0).unwrap(), 1_060_000);
+    }
+
+    #[test]
+    fn now_leaves_out_paused_time() {
+        let clock = LobbyClock {
+            paused: 10_000,
+            ..frozen()
+        };
+
+        assert_eq!(clock.now(EVENT).unwrap(), EVENT as u128 * 1000 + 50_000);
+    }
+
+    #[test]
+    fn now_adds_the_offset() {
+        let ahead = LobbyClock {
+            offset: 3600,
+            ..frozen()
+        };
+        let behind = LobbyClock {
+            offset: -30,
+            ..frozen()
+        };
+
+        assert_eq!(ahead.now(EVENT).unwrap(), EVENT as u128 * 1000 + 3_660_000);
+        assert_eq!(behind.now(EVENT).unwrap(), EVENT as u128 * 1000 + 30_000);
+    }
+
+    #[test]
+    fn now_does_not_go_below_zero() {
+        let clock = LobbyClock {
+            offset: -10_000,
+            ..frozen()
+        };
+
+        assert_eq!(clock.now(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn select_keeps_a_frozen_clock_frozen() {
+        let mut clock = LobbyClock {
+            paused: 5_000,
+            ..frozen()
+        };
+        clock.select().unwrap();
+
+        assert_eq!(clock.paused, 0);
+        assert_eq!(clock.frozen_at, Some(clock.selected_at));
+        assert_eq!(clock.now(EVENT).unwrap(), EVENT as u128 * 1000);
+    }
+
+    #[test]
+    fn select_keeps_a_running_clock_running() {
+        let mut clock = LobbyClock::default();
+        clock.select().unwrap();
+
+        assert_ne!(clock.selected_at, 0);
+        assert_eq!(clock.frozen_at, None);
+    }
+
+    #[test]
+    fn freeze_pins_the_start() {
+        let mut clock = LobbyClock::default();
+        clock.freeze(true).unwrap();
+
+        assert_ne!(clock.selected_at, 0);
+        assert!(clock.frozen_at.is_some());
+    }
+
+    #[test]
+    fn freeze_twice_keeps_the_frozen_time() {
+        let mut clock = frozen();
+        clock.freeze(true).unwrap();
+
+        assert_eq!(clock, frozen());
+    }
+
+    #[test]
+    fn resume_continues_at_the_frozen_time() {
+        let mut clock = frozen();
+        let frozen_time = clock.now(EVENT).unwrap();
+        clock.freeze(false).unwrap();
+
+        assert_eq!(clock.frozen_at, None);
+        // the clock runs again from the frozen time, so only the time since resuming counts
+        let resumed = clock.now(EVENT).unwrap();
+        assert!(resumed >= frozen_time);
+        assert!(resumed - frozen_time < 60_000);
+    }
+}
@@ -96,6 +96,11 @@ impl Database {
             .clone()
             .session_store)
     }
+
+    /// starts a transaction, which is rolled back if it is dropped without a commit
+    pub async fn begin(&self) -> Result<sqlx::Transaction<'static, Sqlite>, sqlx::Error> {
+        self.pool.begin().await
+    }
 }
 
 impl<'c> sqlx::Executor<'c> for &Database {
@@ -7,6 +7,8 @@ pub mod database;
 pub mod logger;
 #[cfg(feature = "server")]
 pub mod protos;
+#[cfg(feature = "server")]
+pub mod storage;
 pub mod util;
 
 #[macro_export]
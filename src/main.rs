@@ -22,7 +22,9 @@ pub fn main() -> anyhow::Result<()> {
     #[cfg(feature = "server")]
     {
         use tracing::info;
-        use tsto_server::util::UPTIME;
+        use tsto_server::{
+            app::controllers::events::EventController, config::OPTIONS, util::UPTIME,
+        };
 
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -31,9 +33,20 @@ pub fn main() -> anyhow::Result<()> {
             .block_on(async {
                 tsto_server::logger::init()?;
                 tsto_server::database::init().await?;
+                tsto_server::storage::init()?;
+
+                let migrate_storage = OPTIONS.take().migrate_storage;
+                if let Some(from) = migrate_storage {
+                    let migrated = tsto_server::storage::migrate(from).await?;
+                    info!("migrated {migrated} saves from {from} storage");
+
+                    return Ok(());
+                }
 
                 info!("initializing server");
                 let router = tsto_server::app::create_router().await?;
+                tokio::spawn(EventController::default().run_rotation());
+                tokio::spawn(tsto_server::config::watch());
                 let listener = tsto_server::app::create_listener().await?;
 
                 // access UPTIME to initialize it as it is behind a lazy_static
@@ -10,26 +10,150 @@ where
     E: From<prost::EncodeError>,
     E: From<prost::DecodeError>,
 {
+    /// reads and decodes the message stored at `path`.
+    ///
+    /// falls back to the previous generation (`<path>.bak`) if the file can not be read or decoded
     fn load(path: impl AsRef<std::path::Path>) -> Result<Self, E> {
-        use std::io::Read;
-        let mut file = std::fs::File::open(&path)?;
-        let mut buffer = Vec::new();
+        let path = path.as_ref();
 
-        file.read_to_end(&mut buffer)?;
-        Ok(Self::decode(&buffer[..])?)
+        match read_message(path) {
+            Ok(message) => Ok(message),
+            Err(error) => {
+                let backup = path_with_suffix(path, BACKUP_SUFFIX);
+
+                if !backup.exists() {
+                    return Err(error);
+                }
+
+                tracing::warn!("failed to load {path:?}, falling back to {backup:?}");
+
+                read_message(&backup)
+            }
+        }
     }
 
+    /// reads the raw message stored at `path` without re-encoding it.
+    ///
+    /// the message is decoded for validation only, so fields unknown to our protos are kept.
+    /// falls back to the previous generation (`<path>.bak`) if the file can not be read or decoded
+    fn load_raw(path: impl AsRef<std::path::Path>) -> Result<Vec<u8>, E> {
+        let path = path.as_ref();
+
+        match read_raw::<Self, E>(path) {
+            Ok(buffer) => Ok(buffer),
+            Err(error) => {
+                let backup = path_with_suffix(path, BACKUP_SUFFIX);
+
+                if !backup.exists() {
+                    return Err(error);
+                }
+
+                tracing::warn!("failed to load {path:?}, falling back to {backup:?}");
+
+                read_raw::<Self, E>(&backup)
+            }
+        }
+    }
+
+    /// atomically replaces the message stored at `path`.
+    ///
+    /// see [`MessageFromPath::save_raw`]
     fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), E> {
         use prost::Message;
-        use std::io::Write;
-        let mut file = std::fs::File::create(&path)?;
         let mut buffer = vec![];
 
         Message::encode(self, &mut buffer)?;
-        file.write_all(&buffer[..])?;
+
+        Self::save_raw(&buffer, path)
+    }
+
+    /// atomically replaces the message stored at `path` with the already encoded `buffer`.
+    ///
+    /// the message is written to a temporary file which gets synced to disk and renamed to `path` afterwards.
+    /// the previous generation is kept as `<path>.bak`
+    fn save_raw(buffer: &[u8], path: impl AsRef<std::path::Path>) -> Result<(), E> {
+        use std::io::Write;
+        let path = path.as_ref();
+        let temp = path_with_suffix(path, TEMP_SUFFIX);
+
+        {
+            let mut file = std::fs::File::create(&temp)?;
+            file.write_all(buffer)?;
+            file.sync_all()?;
+        }
+
+        if path.exists() {
+            let backup = path_with_suffix(path, BACKUP_SUFFIX);
+
+            match std::fs::remove_file(&backup) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+
+            // hard links keep the previous generation without copying, but are not supported by every filesystem
+            if std::fs::hard_link(path, &backup).is_err() {
+                std::fs::copy(path, &backup)?;
+            }
+        }
+
+        std::fs::rename(&temp, path)?;
+
+        #[cfg(unix)]
+        std::fs::File::open(parent_dir(path))?.sync_all()?;
 
         Ok(())
     }
+
+    /// checks if either the message at `path` or its previous generation exists
+    fn exists(path: impl AsRef<std::path::Path>) -> bool {
+        let path = path.as_ref();
+
+        path.exists() || path_with_suffix(path, BACKUP_SUFFIX).exists()
+    }
+}
+
+const BACKUP_SUFFIX: &str = "bak";
+const TEMP_SUFFIX: &str = "tmp";
+
+fn path_with_suffix(path: &std::path::Path, suffix: &str) -> std::path::PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+
+    path.into()
+}
+
+/// directory of `path`, bare file names are in the working directory
+#[cfg(unix)]
+fn parent_dir(path: &std::path::Path) -> &std::path::Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    }
+}
+
+fn read_raw<T, E>(path: &std::path::Path) -> Result<Vec<u8>, E>
+where
+    T: prost::Message + Default,
+    E: From<std::io::Error> + From<prost::DecodeError>,
+{
+    let buffer = std::fs::read(path)?;
+
+    T::decode(&buffer[..])?;
+    Ok(buffer)
+}
+
+fn read_message<T, E>(path: &std::path::Path) -> Result<T, E>
+where
+    T: prost::Message + Default,
+    E: From<std::io::Error> + From<prost::DecodeError>,
+{
+    use std::io::Read;
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = Vec::new();
+
+    file.read_to_end(&mut buffer)?;
+    Ok(T::decode(&buffer[..])?)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -61,5 +185,482 @@ impl data::LandMessage {
     }
 }
 
+impl data::CurrencyData {
+    /// donuts spent so far.
+    ///
+    /// the client only knows awarded, purchased and the balance, so spending is derived from those
+    pub fn vc_total_spent(&self) -> i64 {
+        self.vc_total_purchased.unwrap_or_default() as i64
+            + self.vc_total_awarded.unwrap_or_default() as i64
+            - self.vc_balance.unwrap_or_default() as i64
+    }
+
+    /// applies a delta reported by the client. positive amounts are awarded, negative amounts are spent.
+    ///
+    /// returns `false` and leaves the currency untouched if the delta would take the balance below zero
+    pub fn apply_delta(&mut self, amount: i32) -> bool {
+        let Some(balance) = self
+            .vc_balance
+            .unwrap_or_default()
+            .checked_add(amount)
+            .filter(|balance| *balance >= 0)
+        else {
+            return false;
+        };
+
+        if amount > 0 {
+            let Some(awarded) = self
+                .vc_total_awarded
+                .unwrap_or_default()
+                .checked_add(amount)
+            else {
+                return false;
+            };
+
+            self.vc_total_awarded = Some(awarded);
+        }
+
+        self.vc_balance = Some(balance);
+
+        true
+    }
+
+    /// credits `amount` purchased donuts. returns `false` and leaves the currency untouched on overflow
+    pub fn apply_purchase(&mut self, amount: i32) -> bool {
+        let (Some(purchased), Some(balance)) = (
+            self.vc_total_purchased
+                .unwrap_or_default()
+                .checked_add(amount),
+            self.vc_balance.unwrap_or_default().checked_add(amount),
+        ) else {
+            return false;
+        };
+
+        self.vc_total_purchased = Some(purchased);
+        self.vc_balance = Some(balance);
+
+        true
+    }
+}
+
+/// a land save kept as the raw bytes sent by the client.
+///
+/// prost drops fields unknown to `LandData.proto` when re-encoding a message,
+/// so the decoded `land` is only used for validation and inspection while `bytes` get stored and served.
+#[derive(Debug, Clone)]
+pub struct RawLand {
+    pub land: data::LandMessage,
+    pub bytes: bytes::Bytes,
+}
+
+impl RawLand {
+    pub fn decode(bytes: impl Into<bytes::Bytes>) -> Result<Self, LandError> {
+        let bytes = bytes.into();
+        let land = data::LandMessage::decode(bytes.clone())?;
+
+        Ok(Self { land, bytes })
+    }
+
+    /// overrides the land id without touching any other field
+    pub fn set_id(&mut self, id: &str) {
+        let mut bytes = bytes::BytesMut::from(&self.bytes[..]);
+
+        // parsers keep the last occurrence of a non-repeated field, so appending the id overrides the stored one
+        prost::encoding::string::encode(1, &id.to_owned(), &mut bytes);
+
+        self.bytes = bytes.freeze();
+        self.land.id = Some(id.to_owned());
+    }
+}
+
+/// matchmaking response which serves the matched land as stored, see `RawLand`
+#[derive(Debug, Clone)]
+pub struct RawMatchmakingResponse {
+    pub response: data::MatchmakingResponseMessage,
+    pub bytes: bytes::Bytes,
+}
+
+impl RawMatchmakingResponse {
+    pub fn matched(land: RawLand) -> Self {
+        let mut bytes = bytes::BytesMut::new();
+
+        // embedded messages are encoded like bytes, so the stored land is written as it is
+        prost::encoding::bytes::encode(1, &land.bytes, &mut bytes);
+
+        Self {
+            response: data::MatchmakingResponseMessage {
+                matched_user_land: Some(land.land),
+                error: None,
+            },
+            bytes: bytes.freeze(),
+        }
+    }
+}
+
+/// land backup response which serves the revisions as stored, see `RawLand`
+#[derive(Debug, Clone)]
+pub struct RawLandBackupResponse {
+    pub response: data::LandBackupResponseMessage,
+    pub bytes: bytes::Bytes,
+}
+
+impl RawLandBackupResponse {
+    /// builds the response of `revisions` and the date each got stored at
+    pub fn new(revisions: impl IntoIterator<Item = (u64, RawLand)>) -> Self {
+        let mut response = data::LandBackupResponseMessage::default();
+        let mut bytes = bytes::BytesMut::new();
+
+        for (date, land) in revisions {
+            let mut backup = Vec::new();
+
+            prost::encoding::uint64::encode(1, &date, &mut backup);
+            // embedded messages are encoded like bytes, so the stored land is written as it is
+            prost::encoding::bytes::encode(2, &land.bytes, &mut backup);
+            prost::encoding::bytes::encode(1, &backup, &mut bytes);
+
+            response
+                .land_backup
+                .push(data::land_backup_response_message::LandBackup {
+                    date: Some(date),
+                    land: Some(land.land),
+                });
+        }
+
+        Self {
+            response,
+            bytes: bytes.freeze(),
+        }
+    }
+}
+
+impl From<data::MatchmakingResponseMessage> for RawMatchmakingResponse {
+    fn from(response: data::MatchmakingResponseMessage) -> Self {
+        Self {
+            bytes: response.encode_to_vec().into(),
+            response,
+        }
+    }
+}
+
+impl From<data::LandMessage> for RawLand {
+    fn from(land: data::LandMessage) -> Self {
+        Self {
+            bytes: land.encode_to_vec().into(),
+            land,
+        }
+    }
+}
+
 impl MessageFromPath<CurrencyError> for data::CurrencyData {}
 impl MessageFromPath<LandError> for data::LandMessage {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        data::{CurrencyData, LandMessage},
+        path_with_suffix, CurrencyError, MessageFromPath, RawLand, RawLandBackupResponse,
+        RawMatchmakingResponse, BACKUP_SUFFIX, TEMP_SUFFIX,
+    };
+    use crate::util::protobuf::RawProtobuf;
+    use prost::Message;
+    use std::path::{Path, PathBuf};
+
+    fn currency(balance: i32) -> CurrencyData {
+        CurrencyData {
+            vc_balance: Some(balance),
+            ..Default::default()
+        }
+    }
+
+    fn load(path: &Path) -> Result<CurrencyData, CurrencyError> {
+        CurrencyData::load(path)
+    }
+
+    /// saves the generations `1` and `2`, so `2` is stored and `1` is the backup
+    fn saved_twice(dir: &tempfile::TempDir) -> PathBuf {
+        let path = dir.path().join("currency.pb");
+
+        currency(1).save(&path).unwrap();
+        currency(2).save(&path).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn save_writes_the_message_without_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("currency.pb");
+
+        currency(1).save(&path).unwrap();
+
+        assert_eq!(load(&path).unwrap(), currency(1));
+        assert!(!path_with_suffix(&path, TEMP_SUFFIX).exists());
+        assert!(!path_with_suffix(&path, BACKUP_SUFFIX).exists());
+    }
+
+    #[test]
+    fn save_keeps_the_previous_generation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = saved_twice(&dir);
+        let backup = path_with_suffix(&path, BACKUP_SUFFIX);
+
+        assert_eq!(load(&path).unwrap(), currency(2));
+        assert_eq!(load(&backup).unwrap(), currency(1));
+
+        currency(3).save(&path).unwrap();
+
+        assert_eq!(load(&path).unwrap(), currency(3));
+        assert_eq!(load(&backup).unwrap(), currency(2));
+        assert!(!path_with_suffix(&path, TEMP_SUFFIX).exists());
+    }
+
+    #[test]
+    fn load_falls_back_to_the_backup_if_the_file_is_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = saved_twice(&dir);
+
+        std::fs::write(&path, [0xff; 8]).unwrap();
+
+        assert_eq!(load(&path).unwrap(), currency(1));
+    }
+
+    #[test]
+    fn load_falls_back_to_the_backup_if_the_file_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = saved_twice(&dir);
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(CurrencyData::exists(&path));
+        assert_eq!(load(&path).unwrap(), currency(1));
+    }
+
+    #[test]
+    fn load_fails_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("currency.pb");
+
+        assert!(!CurrencyData::exists(&path));
+        assert!(load(&path).is_err());
+
+        std::fs::write(&path, [0xff; 8]).unwrap();
+
+        assert!(load(&path).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bare_file_names_sync_the_working_directory() {
+        assert_eq!(super::parent_dir(Path::new("currency.pb")), Path::new("."));
+        assert_eq!(
+            super::parent_dir(Path::new("saves/currency.pb")),
+            Path::new("saves")
+        );
+    }
+
+    /// land with a field unknown to `LandData.proto`, like newer clients may send
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct FutureLand {
+        #[prost(string, optional, tag = "1")]
+        id: Option<String>,
+        #[prost(string, optional, tag = "1000")]
+        unknown: Option<String>,
+    }
+
+    fn future_land(id: &str) -> FutureLand {
+        FutureLand {
+            id: Some(id.to_owned()),
+            unknown: Some("kept".to_owned()),
+        }
+    }
+
+    #[test]
+    fn save_raw_keeps_unknown_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("land.pb");
+        let buffer = future_land("1").encode_to_vec();
+
+        LandMessage::save_raw(&buffer, &path).unwrap();
+
+        assert_eq!(LandMessage::load_raw(&path).unwrap(), buffer);
+    }
+
+    #[test]
+    fn load_raw_falls_back_to_the_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("land.pb");
+        let buffer = future_land("1").encode_to_vec();
+
+        LandMessage::save_raw(&buffer, &path).unwrap();
+        LandMessage::save_raw(&[0xff; 8], &path).unwrap();
+
+        assert_eq!(LandMessage::load_raw(&path).unwrap(), buffer);
+    }
+
+    #[test]
+    fn set_id_keeps_unknown_fields() {
+        let mut land = RawLand::decode(future_land("old").encode_to_vec()).unwrap();
+
+        land.set_id("new");
+
+        assert_eq!(land.land.id.as_deref(), Some("new"));
+        assert_eq!(FutureLand::decode(land.bytes).unwrap(), future_land("new"));
+    }
+
+    #[tokio::test]
+    async fn raw_responses_keep_unknown_fields() {
+        use axum::response::IntoResponse;
+
+        let mut land = RawLand::decode(future_land("old").encode_to_vec()).unwrap();
+        land.set_id("new");
+
+        let response = RawProtobuf(land.land, land.bytes).into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(FutureLand::decode(body).unwrap(), future_land("new"));
+    }
+
+    fn donuts(purchased: i32, awarded: i32, balance: i32) -> CurrencyData {
+        CurrencyData {
+            vc_total_purchased: Some(purchased),
+            vc_total_awarded: Some(awarded),
+            vc_balance: Some(balance),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn apply_delta_awards_positive_amounts() {
+        let mut currency = donuts(5, 10, 15);
+
+        assert!(currency.apply_delta(20));
+        assert_eq!(currency, donuts(5, 30, 35));
+        assert_eq!(currency.vc_total_spent(), 0);
+    }
+
+    #[test]
+    fn apply_delta_spends_negative_amounts() {
+        let mut currency = donuts(5, 10, 15);
+
+        assert!(currency.apply_delta(-12));
+        assert_eq!(currency, donuts(5, 10, 3));
+        assert_eq!(currency.vc_total_spent(), 12);
+    }
+
+    #[test]
+    fn apply_delta_rejects_overdrafts() {
+        let mut currency = donuts(5, 10, 15);
+
+        assert!(!currency.apply_delta(-16));
+        assert_eq!(currency, donuts(5, 10, 15));
+
+        assert!(currency.apply_delta(-15));
+        assert_eq!(currency.vc_balance, Some(0));
+    }
+
+    #[test]
+    fn apply_delta_rejects_overflows() {
+        let mut full_balance = donuts(0, 0, i32::MAX);
+        let mut full_awarded = donuts(0, i32::MAX, 0);
+
+        assert!(!full_balance.apply_delta(1));
+        assert_eq!(full_balance, donuts(0, 0, i32::MAX));
+        assert!(!full_awarded.apply_delta(1));
+        assert_eq!(full_awarded, donuts(0, i32::MAX, 0));
+    }
+
+    #[test]
+    fn vc_total_spent_does_not_overflow() {
+        let currency = donuts(i32::MAX, i32::MAX, 0);
+
+        assert_eq!(currency.vc_total_spent(), 2 * i32::MAX as i64);
+    }
+
+    #[test]
+    fn apply_purchase_credits_purchased_donuts() {
+        let mut currency = donuts(5, 10, 3);
+
+        assert!(currency.apply_purchase(100));
+        assert_eq!(currency, donuts(105, 10, 103));
+        assert_eq!(currency.vc_total_spent(), 12);
+    }
+
+    #[test]
+    fn apply_purchase_rejects_overflows() {
+        let mut full_balance = donuts(0, 0, i32::MAX);
+        let mut full_purchased = donuts(i32::MAX, 0, 0);
+
+        assert!(!full_balance.apply_purchase(1));
+        assert_eq!(full_balance, donuts(0, 0, i32::MAX));
+        assert!(!full_purchased.apply_purchase(1));
+        assert_eq!(full_purchased, donuts(i32::MAX, 0, 0));
+    }
+
+    #[test]
+    fn matched_responses_keep_unknown_fields() {
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct FutureResponse {
+            #[prost(message, optional, tag = "1")]
+            matched_user_land: Option<FutureLand>,
+        }
+
+        let mut land = RawLand::decode(future_land("old").encode_to_vec()).unwrap();
+        land.set_id("new");
+
+        let response = RawMatchmakingResponse::matched(land);
+
+        assert_eq!(
+            response.response.matched_user_land.and_then(|land| land.id),
+            Some("new".to_owned())
+        );
+        assert_eq!(
+            FutureResponse::decode(response.bytes).unwrap(),
+            FutureResponse {
+                matched_user_land: Some(future_land("new")),
+            }
+        );
+    }
+
+    #[test]
+    fn land_backups_keep_unknown_fields() {
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct FutureBackup {
+            #[prost(uint64, optional, tag = "1")]
+            date: Option<u64>,
+            #[prost(message, optional, tag = "2")]
+            land: Option<FutureLand>,
+        }
+
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct FutureResponse {
+            #[prost(message, repeated, tag = "1")]
+            land_backup: Vec<FutureBackup>,
+        }
+
+        let revisions = ["first", "second"].map(|id| future_land(id).encode_to_vec());
+        let response = RawLandBackupResponse::new(
+            (1u64..)
+                .zip(&revisions)
+                .map(|(date, land)| (date, RawLand::decode(land.clone()).unwrap())),
+        );
+
+        assert_eq!(response.response.land_backup.len(), 2);
+        assert_eq!(
+            FutureResponse::decode(response.bytes).unwrap(),
+            FutureResponse {
+                land_backup: vec![
+                    FutureBackup {
+                        date: Some(1),
+                        land: Some(future_land("first")),
+                    },
+                    FutureBackup {
+                        date: Some(2),
+                        land: Some(future_land("second")),
+                    },
+                ],
+            }
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    config::OPTIONS,
+    protos::{data::CurrencyData, CurrencyError, LandError},
+};
+use axum::async_trait;
+use bytes::Bytes;
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+mod filesystem;
+mod lock;
+mod sqlite;
+
+pub use filesystem::FileStorage;
+pub use lock::{lock_save, SaveGuard};
+pub use sqlite::SqliteStorage;
+
+pub static STORAGE: OnceCell<Arc<dyn SaveStorage>> = OnceCell::new();
+
+pub fn init() -> anyhow::Result<()> {
+    let backend = OPTIONS.take().storage;
+
+    info!("using {backend} save storage");
+    STORAGE
+        .set(backend.create())
+        .expect("storage was not initialized yet");
+
+    Ok(())
+}
+
+/// copies every save stored in `from` into the configured storage backend
+pub async fn migrate(from: StorageBackend) -> anyhow::Result<usize> {
+    let to = OPTIONS.take().storage;
+
+    if from == to {
+        anyhow::bail!("can not migrate {from} storage into itself");
+    }
+
+    info!("migrating saves from {from} to {to} storage");
+
+    let source = from.create();
+    let target = to.create();
+    let mut migrated = 0;
+
+    for mayhem_id in source.saves().await? {
+        debug!("migrating save of {mayhem_id}");
+
+        if let Some(land) = source.load_land(&mayhem_id).await? {
+            target.save_land(&mayhem_id, &land).await?;
+        }
+        if let Some(currency) = source.load_currency(&mayhem_id).await? {
+            target.save_currency(&mayhem_id, &currency).await?;
+        }
+
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// stores saves as `<mayhem_id>/land.pb` and `<mayhem_id>/currency.pb` in the data directory
+    #[default]
+    Filesystem,
+    /// stores saves as blobs in the server database
+    Sqlite,
+}
+
+impl StorageBackend {
+    pub fn create(&self) -> Arc<dyn SaveStorage> {
+        match self {
+            StorageBackend::Filesystem => Arc::new(FileStorage::default()),
+            StorageBackend::Sqlite => Arc::new(SqliteStorage::default()),
+        }
+    }
+}
+
+impl std::fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StorageBackend::Filesystem => "filesystem",
+            StorageBackend::Sqlite => "sqlite",
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error(transparent)]
+    Land(#[from] LandError),
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error("failed to execute query")]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+/// persists land and currency saves of users
+#[async_trait]
+pub trait SaveStorage: std::fmt::Debug + Send + Sync {
+    async fn land_exists(&self, mayhem_id: &str) -> Result<bool, StorageError>;
+
+    /// loads the land exactly as it was saved. see [`crate::protos::RawLand`]
+    async fn load_land(&self, mayhem_id: &str) -> Result<Option<Bytes>, StorageError>;
+
+    /// saves the encoded land without re-encoding it. see [`crate::protos::RawLand`]
+    async fn save_land(&self, mayhem_id: &str, land: &[u8]) -> Result<(), StorageError>;
+
+    async fn load_currency(&self, mayhem_id: &str) -> Result<Option<CurrencyData>, StorageError>;
+
+    async fn save_currency(
+        &self,
+        mayhem_id: &str,
+        currency: &CurrencyData,
+    ) -> Result<(), StorageError>;
+
+    /// lists the mayhem ids of all stored saves
+    async fn saves(&self) -> Result<Vec<String>, StorageError>;
+}
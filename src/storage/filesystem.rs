@@ -0,0 +1,124 @@
+use super::{SaveStorage, StorageError};
+use crate::{
+    config::OPTIONS,
+    protos::{
+        data::{CurrencyData, LandMessage},
+        MessageFromPath,
+    },
+    util::{relative_path, DIRECTORIES},
+};
+use axum::async_trait;
+use bytes::Bytes;
+use std::{
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+};
+
+const LAND_FILE: &str = "land.pb";
+const CURRENCY_FILE: &str = "currency.pb";
+
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    root: PathBuf,
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        let root = if OPTIONS.take().portable {
+            relative_path().expect("curent relative path retrieves successfully")
+        } else {
+            DIRECTORIES.data_local_dir().to_path_buf()
+        };
+
+        Self { root }
+    }
+}
+
+impl FileStorage {
+    fn path(&self, mayhem_id: &str, file: &str) -> PathBuf {
+        self.root.join(mayhem_id).join(file)
+    }
+
+    fn create_parent(path: &Path) -> Result<(), StorageError> {
+        let parent = path.parent().expect("data dir exists");
+
+        if !parent.exists() {
+            create_dir_all(parent)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SaveStorage for FileStorage {
+    async fn land_exists(&self, mayhem_id: &str) -> Result<bool, StorageError> {
+        Ok(LandMessage::exists(self.path(mayhem_id, LAND_FILE)))
+    }
+
+    async fn load_land(&self, mayhem_id: &str) -> Result<Option<Bytes>, StorageError> {
+        let path = self.path(mayhem_id, LAND_FILE);
+
+        if !LandMessage::exists(&path) {
+            return Ok(None);
+        }
+
+        Ok(Some(LandMessage::load_raw(&path)?.into()))
+    }
+
+    async fn save_land(&self, mayhem_id: &str, land: &[u8]) -> Result<(), StorageError> {
+        let path = self.path(mayhem_id, LAND_FILE);
+
+        Self::create_parent(&path)?;
+        LandMessage::save_raw(land, &path)?;
+
+        Ok(())
+    }
+
+    async fn load_currency(&self, mayhem_id: &str) -> Result<Option<CurrencyData>, StorageError> {
+        let path = self.path(mayhem_id, CURRENCY_FILE);
+
+        if !CurrencyData::exists(&path) {
+            return Ok(None);
+        }
+
+        Ok(Some(CurrencyData::load(&path)?))
+    }
+
+    async fn save_currency(
+        &self,
+        mayhem_id: &str,
+        currency: &CurrencyData,
+    ) -> Result<(), StorageError> {
+        let path = self.path(mayhem_id, CURRENCY_FILE);
+
+        Self::create_parent(&path)?;
+        currency.save(&path)?;
+
+        Ok(())
+    }
+
+    async fn saves(&self) -> Result<Vec<String>, StorageError> {
+        let mut saves = vec![];
+
+        if !self.root.exists() {
+            return Ok(saves);
+        }
+
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+
+            if !LandMessage::exists(path.join(LAND_FILE))
+                && !CurrencyData::exists(path.join(CURRENCY_FILE))
+            {
+                continue;
+            }
+
+            if let Some(mayhem_id) = path.file_name().and_then(|name| name.to_str()) {
+                saves.push(mayhem_id.to_owned());
+            }
+        }
+
+        Ok(saves)
+    }
+}
@@ -0,0 +1,30 @@
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::OwnedMutexGuard;
+
+pub type SaveGuard = OwnedMutexGuard<()>;
+
+lazy_static! {
+    static ref SAVE_LOCKS: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// acquires the save lock of `mayhem_id`.
+///
+/// every read-modify-write of a user's land or currency has to hold this guard,
+/// so concurrent requests of the same user are applied one after another instead of overwriting each other
+pub async fn lock_save(mayhem_id: &str) -> SaveGuard {
+    let lock = {
+        let mut locks = SAVE_LOCKS.lock().unwrap();
+
+        // drop locks which are neither held nor awaited anymore
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+        locks.entry(mayhem_id.to_owned()).or_default().clone()
+    };
+
+    lock.lock_owned().await
+}
@@ -0,0 +1,122 @@
+use super::{SaveStorage, StorageError};
+use crate::{
+    database::Database,
+    protos::{
+        data::{CurrencyData, LandMessage},
+        CurrencyError, LandError,
+    },
+    util::millis_from_unix_epoch,
+};
+use axum::async_trait;
+use bytes::Bytes;
+use prost::Message;
+
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    db: Database,
+}
+
+impl Default for SqliteStorage {
+    fn default() -> Self {
+        Self {
+            db: crate::database::DATABASE
+                .get()
+                .expect("database is initialized")
+                .clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl SaveStorage for SqliteStorage {
+    async fn land_exists(&self, mayhem_id: &str) -> Result<bool, StorageError> {
+        const QUERY: &str = "SELECT COUNT() FROM saves WHERE mayhem_id = ? AND land IS NOT NULL";
+
+        Ok(sqlx::query_scalar::<_, i64>(QUERY)
+            .bind(mayhem_id)
+            .fetch_one(&self.db)
+            .await?
+            > 0)
+    }
+
+    async fn load_land(&self, mayhem_id: &str) -> Result<Option<Bytes>, StorageError> {
+        const QUERY: &str = "SELECT land FROM saves WHERE mayhem_id = ?";
+
+        match sqlx::query_scalar::<_, Option<Vec<u8>>>(QUERY)
+            .bind(mayhem_id)
+            .fetch_optional(&self.db)
+            .await?
+            .flatten()
+        {
+            Some(land) => {
+                // validate only, the raw bytes are returned to keep unknown fields
+                LandMessage::decode(&land[..]).map_err(LandError::DecodeError)?;
+
+                Ok(Some(land.into()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn save_land(&self, mayhem_id: &str, land: &[u8]) -> Result<(), StorageError> {
+        const QUERY: &str = r#"
+            INSERT INTO saves (mayhem_id, land, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(mayhem_id) DO UPDATE
+            SET land = excluded.land, updated_at = excluded.updated_at"#;
+
+        sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(land)
+            .bind(millis_from_unix_epoch()? as i64)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_currency(&self, mayhem_id: &str) -> Result<Option<CurrencyData>, StorageError> {
+        const QUERY: &str = "SELECT currency FROM saves WHERE mayhem_id = ?";
+
+        match sqlx::query_scalar::<_, Option<Vec<u8>>>(QUERY)
+            .bind(mayhem_id)
+            .fetch_optional(&self.db)
+            .await?
+            .flatten()
+        {
+            Some(currency) => Ok(Some(
+                CurrencyData::decode(&currency[..]).map_err(CurrencyError::DecodeError)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_currency(
+        &self,
+        mayhem_id: &str,
+        currency: &CurrencyData,
+    ) -> Result<(), StorageError> {
+        const QUERY: &str = r#"
+            INSERT INTO saves (mayhem_id, currency, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(mayhem_id) DO UPDATE
+            SET currency = excluded.currency, updated_at = excluded.updated_at"#;
+
+        sqlx::query(QUERY)
+            .bind(mayhem_id)
+            .bind(currency.encode_to_vec())
+            .bind(millis_from_unix_epoch()? as i64)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn saves(&self) -> Result<Vec<String>, StorageError> {
+        const QUERY: &str = "SELECT mayhem_id FROM saves";
+
+        Ok(sqlx::query_scalar::<_, String>(QUERY)
+            .fetch_all(&self.db)
+            .await?)
+    }
+}
@@ -1,4 +1,6 @@
 #[cfg(feature = "server")]
+pub mod config;
+#[cfg(feature = "server")]
 mod credits;
 pub mod error;
 #[cfg(feature = "server")]
@@ -12,9 +14,10 @@ pub mod xml;
 pub use r#mod::*;
 #[cfg(feature = "server")]
 mod r#mod {
+    pub use super::config::{config_path, config_path_for, CachedToml};
     pub use super::credits::CREDITS;
     pub use super::error::ErrorMessage;
-    pub use super::protobuf::Protobuf;
+    pub use super::protobuf::{Protobuf, RawProtobuf};
     pub use super::xml::Xml;
     use directories::ProjectDirs;
     use lazy_static::lazy_static;
@@ -0,0 +1,124 @@
+use super::{relative_path, DIRECTORIES};
+use crate::config::OPTIONS;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{path::PathBuf, sync::RwLock, time::SystemTime};
+use tracing::{error, info};
+
+/// path of `file` in the config dir, or next to the executable in portable mode
+pub fn config_path(file: &str) -> std::io::Result<PathBuf> {
+    let portable = OPTIONS.take().portable;
+
+    config_path_for(portable, file)
+}
+
+/// like `config_path`, for the files which are read before `OPTIONS` exists
+pub fn config_path_for(portable: bool, file: &str) -> std::io::Result<PathBuf> {
+    let mut path = if portable {
+        relative_path()?
+    } else {
+        DIRECTORIES.config_local_dir().to_path_buf()
+    };
+    path.push(file);
+
+    Ok(path)
+}
+
+/// checks a parsed file beyond its format, the error describes what is wrong
+pub type Validator<T> = fn(&T) -> Result<(), String>;
+
+/// a toml file of the config dir which is parsed again whenever it changes.
+///
+/// a missing file means the default, an invalid file is logged and the previous value is kept
+pub struct CachedToml<T> {
+    file: &'static str,
+    default: fn() -> T,
+    validate: Option<Validator<T>>,
+    /// last successfully parsed value and the modification time of the file
+    cache: RwLock<Option<(SystemTime, T)>>,
+}
+
+impl<T> CachedToml<T>
+where
+    T: Clone + DeserializeOwned + Serialize,
+{
+    pub const fn new(file: &'static str, default: fn() -> T) -> Self {
+        Self {
+            file,
+            default,
+            validate: None,
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// like `new`, but files which do not pass `validate` are treated as invalid
+    pub const fn validated(file: &'static str, default: fn() -> T, validate: Validator<T>) -> Self {
+        Self {
+            file,
+            default,
+            validate: Some(validate),
+            cache: RwLock::new(None),
+        }
+    }
+
+    pub fn path(&self) -> std::io::Result<PathBuf> {
+        config_path(self.file)
+    }
+
+    /// returns the current content of the file, which is only read again if it changed since the last read
+    pub fn load(&self) -> std::io::Result<T> {
+        let path = self.path()?;
+
+        let Ok(modified) = path.metadata().and_then(|meta| meta.modified()) else {
+            *self.cache.write().unwrap() = None;
+
+            return Ok((self.default)());
+        };
+
+        if let Some((loaded, value)) = self.cache.read().unwrap().as_ref() {
+            if *loaded == modified {
+                return Ok(value.clone());
+            }
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let mut cache = self.cache.write().unwrap();
+
+        let parsed = toml::from_str::<T>(&content)
+            .map_err(|e| e.to_string())
+            .and_then(|value| match self.validate {
+                Some(validate) => validate(&value).map(|_| value),
+                None => Ok(value),
+            });
+
+        let value = match parsed {
+            Ok(value) => {
+                info!("loaded {}", path.display());
+
+                value
+            }
+            Err(e) => {
+                error!("failed to load {}: {e}", path.display());
+
+                // remember the broken file, so it is not parsed again until it changes
+                cache
+                    .as_ref()
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_else(self.default)
+            }
+        };
+        *cache = Some((modified, value.clone()));
+
+        Ok(value)
+    }
+
+    /// replaces the file with `value`, which is read again on the next `load`
+    pub fn save<E>(&self, value: &T) -> Result<(), E>
+    where
+        E: From<std::io::Error> + From<toml::ser::Error>,
+    {
+        std::fs::write(self.path()?, toml::to_string_pretty(value)?)?;
+        *self.cache.write().unwrap() = None;
+
+        Ok(())
+    }
+}
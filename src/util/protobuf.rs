@@ -36,6 +36,42 @@ where
 
 axum_core::__impl_deref!(Protobuf);
 
+/// Protobuf extractor and response which keeps the raw body next to the decoded message.
+///
+/// Responses are sent as the raw bytes, so fields unknown to our protos survive a round trip.
+#[derive(Debug, Clone, Default)]
+pub struct RawProtobuf<T>(pub T, pub Bytes);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for RawProtobuf<T>
+where
+    T: Message + Default,
+    S: Send + Sync,
+{
+    type Rejection = ProtobufRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state).await?;
+
+        match T::decode(bytes.clone()) {
+            Ok(value) => Ok(RawProtobuf(value, bytes)),
+            Err(err) => Err(ProtobufDecodeError::from_err(err).into()),
+        }
+    }
+}
+
+impl<T> IntoResponse for RawProtobuf<T> {
+    fn into_response(self) -> Response {
+        let mut res = self.1.into_response();
+        res.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROTOBUF_CONTENT_TYPE),
+        );
+
+        res
+    }
+}
+
 impl<T> From<T> for Protobuf<T> {
     fn from(inner: T) -> Self {
         Self(inner)
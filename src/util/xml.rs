@@ -13,12 +13,17 @@ macro_rules! xml_response {
         $crate::xml_response!(400, $field)
     };
     ($code:literal, $field:literal) => {
+        $crate::xml_response!($code, "BAD_REQUEST", $field)
+    };
+    ($code:literal, $type:literal, $field:literal) => {
         $crate::util::Xml(
             $code,
             concat!(
                 r#"<?xml version="1.0" encoding="UTF-8"?><error code=""#,
                 $code,
-                r#"" type="BAD_REQUEST" field=""#,
+                r#"" type=""#,
+                $type,
+                r#"" field=""#,
                 $field,
                 r#""/>"#
             )